@@ -57,8 +57,9 @@ programCommand('change-admin')
 programCommand('lock')
     // eslint-disable-next-line @typescript-eslint/no-unused-vars
     .option('-m, --mint <number>')
+    .option('-t, --tier <number>', 'lock tier index', '0')
     .action(async (directory, cmd) => {
-        const { env, keypair, rpc, mint } = cmd.opts();
+        const { env, keypair, rpc, mint, tier } = cmd.opts();
 
         console.log('Solana Cluster:', env);
         console.log('Keypair Path:', keypair);
@@ -70,7 +71,7 @@ programCommand('lock')
             return;
         }
 
-        await lockPnft(new PublicKey(mint));
+        await lockPnft(new PublicKey(mint), parseInt(tier));
     });
 
 programCommand('unlock')
@@ -126,9 +126,10 @@ export const initializeUserPool = async () => {
 
 export const lockPnft = async (
     nftMint: PublicKey,
+    tier: number,
 ) => {
     try {
-        const tx = await createLockPnftTx(payer as Wallet, nftMint, program, solConnection);
+        const tx = await createLockPnftTx(payer as Wallet, nftMint, tier, program, solConnection);
 
         await addAdminSignAndConfirm(tx);
     } catch (e) {
@@ -5,6 +5,21 @@ import {
 
 export const GLOBAL_AUTHORITY_SEED = "global-authority";
 export const USER_POOL_SEED = "user-stake-pool";
+export const CONFIG_CHANGE_SEED = "config-change";
+export const BLOCKLIST_SEED = "blocklist";
+export const TREASURY_SEED = "treasury";
+export const STAKED_NFT_SEED = "staked-nft";
+export const REVENUE_SEED = "revenue";
+export const STAKE_CHECKPOINT_SEED = "stake-checkpoint";
+export const REWARD_VAULT_SEED = "reward-vault";
+export const VESTING_SEED = "vesting";
+export const STORE_ITEM_SEED = "store-item";
+export const REDEMPTION_SEED = "redemption";
+export const VOTER_WEIGHT_RECORD_SEED = "voter-weight-record";
+export const POLL_SEED = "poll";
+export const VOTE_RECORD_SEED = "vote-record";
+export const SNAPSHOT_SEED = "snapshot";
+export const ESCROW_SEED = "escrow";
 
 // export const PROGRAM_ID = new PublicKey("Fc1BDqwDckq8Bi9jHw851H6ppXfRGgms8Zn7ZooznFMG");
 export const PROGRAM_ID = new PublicKey("PLSeoaC7uLWpkbFjUMvF2Er4RXQThdA6T7S9ZY6BqiT");
@@ -14,7 +14,7 @@ import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
 import { PROGRAM_ID as TOKEN_AUTH_RULES_ID } from "@metaplex-foundation/mpl-token-auth-rules";
 
 import { METAPLEX, MPL_DEFAULT_RULE_SET, findTokenRecordPda, getAssociatedTokenAccount, getMasterEdition, getMetadata } from './util';
-import { ADMIN_ADDRESS, GLOBAL_AUTHORITY_SEED, USER_POOL_SEED } from './constant';
+import { ADMIN_ADDRESS, BLOCKLIST_SEED, CONFIG_CHANGE_SEED, GLOBAL_AUTHORITY_SEED, REVENUE_SEED, STAKE_CHECKPOINT_SEED, STAKED_NFT_SEED, TREASURY_SEED, USER_POOL_SEED } from './constant';
 
 export const createInitializeTx = async (
     userAddress: PublicKey,
@@ -25,11 +25,21 @@ export const createInitializeTx = async (
         program.programId);
     console.log("globalPool: ", globalPool.toBase58());
 
+    const [treasury, _treasury_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(TREASURY_SEED)],
+        program.programId);
+
+    const [revenueVault, _revenue_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(REVENUE_SEED)],
+        program.programId);
+
     const txId = await program.methods
         .initialize()
         .accounts({
             admin: userAddress,
             globalPool,
+            treasury,
+            revenueVault,
             systemProgram: SystemProgram.programId,
             rent: SYSVAR_RENT_PUBKEY
         })
@@ -39,7 +49,7 @@ export const createInitializeTx = async (
 }
 
 /**
- * Change admin of the program
+ * Queue admin hand over, it can be executed once the config delay passed
  */
 export const changeAdminTx = async (
     admin: PublicKey,
@@ -50,11 +60,20 @@ export const changeAdminTx = async (
         [Buffer.from(GLOBAL_AUTHORITY_SEED)],
         program.programId);
 
+    const globalInfo = await program.account.globalPool.fetch(globalPool);
+    const [pendingChange, _change_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(CONFIG_CHANGE_SEED), (globalInfo.changeCnt as anchor.BN).toArrayLike(Buffer, 'le', 8)],
+        program.programId);
+    console.log("pendingChange: ", pendingChange.toBase58());
+
     const tx = await program.methods
-        .changeAdmin(newAdminAddr)
+        .queueConfigChange({ admin: { newAdmin: newAdminAddr } })
         .accounts({
             admin,
-            globalPool
+            globalPool,
+            pendingChange,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY
         })
         .transaction();
 
@@ -71,10 +90,15 @@ export const createInitUserTx = async (
 
     console.log("userPool: ", userPool.toBase58());
 
+    const [globalPool, _global_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(GLOBAL_AUTHORITY_SEED)],
+        program.programId);
+
     const txId = await program.methods
         .initUser()
         .accounts({
             user: userAddress,
+            globalPool,
             userPool,
             systemProgram: SystemProgram.programId,
             rent: SYSVAR_RENT_PUBKEY
@@ -84,11 +108,32 @@ export const createInitUserTx = async (
     return txId;
 }
 
+/**
+ * Stake checkpoint of user for current epoch, stake changes record stake count into it
+ */
+export const getStakeCheckpoint = async (
+    userAddress: PublicKey,
+    globalPool: PublicKey,
+    program: anchor.Program
+) => {
+    const globalInfo = await program.account.globalPool.fetch(globalPool);
+    const epoch = Buffer.alloc(4);
+    epoch.writeUInt32LE(globalInfo.epoch as number);
+
+    const [stakeCheckpoint, _checkpoint_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(STAKE_CHECKPOINT_SEED), userAddress.toBuffer(), epoch],
+        program.programId);
+    return stakeCheckpoint;
+}
+
 export const createLockPnftTx = async (
     wallet: Wallet,
     nftMint: PublicKey,
+    tier: number,
     program: anchor.Program,
-    connection: Connection
+    connection: Connection,
+    weight: number = 1,
+    proof: number[][] = []
 ) => {
     const userAddress = wallet.publicKey;
 
@@ -102,6 +147,14 @@ export const createLockPnftTx = async (
         program.programId);
     console.log("userPool: ", userPool.toBase58());
 
+    const [treasury, _treasury_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(TREASURY_SEED)],
+        program.programId);
+
+    const [stakedNft, _staked_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(STAKED_NFT_SEED), nftMint.toBuffer()],
+        program.programId);
+
     const nftEdition = await getMasterEdition(nftMint);
     console.log("nftEdition: ", nftEdition.toBase58());
 
@@ -114,6 +167,17 @@ export const createLockPnftTx = async (
     const tokenMintRecord = findTokenRecordPda(nftMint, tokenAccount);
     console.log("tokenMintRecord: ", tokenMintRecord.toBase58());
 
+    const stakeCheckpoint = await getStakeCheckpoint(userAddress, globalPool, program);
+    console.log("stakeCheckpoint: ", stakeCheckpoint.toBase58());
+
+    const [walletBlocklist, _wallet_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(BLOCKLIST_SEED), userAddress.toBuffer()],
+        program.programId);
+
+    const [mintBlocklist, _mint_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(BLOCKLIST_SEED), nftMint.toBuffer()],
+        program.programId);
+
     const tx = new Transaction();
 
     let poolAccount = await connection.getAccountInfo(userPool);
@@ -124,7 +188,7 @@ export const createLockPnftTx = async (
     }
 
     const txId = await program.methods
-        .lockPnft()
+        .lockPnft(tier, weight, proof, false)
         .accounts({
             admin: ADMIN_ADDRESS,
             globalPool,
@@ -137,9 +201,21 @@ export const createLockPnftTx = async (
             sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
             signer: userAddress,
             userPool,
+            walletBlocklist,
+            mintBlocklist,
+            stakedNft,
+            stakeCheckpoint,
+            // Program id stands for omitted optional accounts
+            escrowTokenAccount: program.programId,
+            receiptMint: program.programId,
+            receiptTokenAccount: program.programId,
+            receiptEscrow: program.programId,
+            receiptEscrowRecord: program.programId,
+            treasury,
             tokenProgram: TOKEN_PROGRAM_ID,
             tokenMetadataProgram: METAPLEX,
             authRulesProgram: TOKEN_AUTH_RULES_ID,
+            associatedTokenProgram: program.programId,
             systemProgram: SystemProgram.programId
         })
         .transaction();
@@ -174,6 +250,14 @@ export const createUnlockPnftTx = async (
         program.programId);
     console.log("userPool: ", userPool.toBase58());
 
+    const [treasury, _treasury_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(TREASURY_SEED)],
+        program.programId);
+
+    const [stakedNft, _staked_bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from(STAKED_NFT_SEED), nftMint.toBuffer()],
+        program.programId);
+
     const nftEdition = await getMasterEdition(nftMint);
     console.log("nftEdition: ", nftEdition.toBase58());
 
@@ -186,6 +270,9 @@ export const createUnlockPnftTx = async (
     const tokenMintRecord = findTokenRecordPda(nftMint, tokenAccount);
     console.log("tokenMintRecord: ", tokenMintRecord.toBase58());
 
+    const stakeCheckpoint = await getStakeCheckpoint(userAddress, globalPool, program);
+    console.log("stakeCheckpoint: ", stakeCheckpoint.toBase58());
+
     const tx = new Transaction();
 
     const txId = await program.methods
@@ -202,9 +289,19 @@ export const createUnlockPnftTx = async (
             sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
             signer: userAddress,
             userPool,
+            stakedNft,
+            stakeCheckpoint,
+            // Program id stands for omitted optional accounts
+            escrowTokenAccount: program.programId,
+            receiptMint: program.programId,
+            receiptTokenAccount: program.programId,
+            receiptEscrow: program.programId,
+            receiptEscrowRecord: program.programId,
+            treasury,
             tokenProgram: TOKEN_PROGRAM_ID,
             tokenMetadataProgram: METAPLEX,
             authRulesProgram: TOKEN_AUTH_RULES_ID,
+            associatedTokenProgram: program.programId,
             systemProgram: SystemProgram.programId
         })
         .transaction();
@@ -3,10 +3,21 @@ import { PublicKey } from '@solana/web3.js'
 
 export interface GlobalPool {
     admin: PublicKey,
+    configDelay: anchor.BN,
+    changeCnt: anchor.BN,
+    lockFee: anchor.BN,
+    unlockFee: anchor.BN,
+    unbondingPeriod: anchor.BN,
 }
 export interface UserPool {
     user: PublicKey,
     stakeCnt: number,
+    stakeWeight: anchor.BN,
+    rewards: anchor.BN[],
+    rewardDebts: anchor.BN[],
+    points: anchor.BN,
+    pointsIndex: anchor.BN,
+    streakStart: anchor.BN,
 }
 
 export interface StakeInfo {
@@ -1,8 +1,127 @@
 pub const GLOBAL_AUTHORITY_SEED: &str = "global-authority";
 pub const USER_POOL_SEED: &str = "user-stake-pool";
+pub const CONFIG_CHANGE_SEED: &str = "config-change";
+pub const BLOCKLIST_SEED: &str = "blocklist";
+pub const TREASURY_SEED: &str = "treasury";
+pub const STAKED_NFT_SEED: &str = "staked-nft";
+pub const REWARD_VAULT_SEED: &str = "reward-vault";
+pub const STORE_ITEM_SEED: &str = "store-item";
+pub const REDEMPTION_SEED: &str = "redemption";
+pub const VESTING_SEED: &str = "vesting";
+pub const REVENUE_SEED: &str = "revenue";
+pub const VOTER_WEIGHT_RECORD_SEED: &str = "voter-weight-record";
+pub const POLL_SEED: &str = "poll";
+pub const VOTE_RECORD_SEED: &str = "vote-record";
+pub const SNAPSHOT_SEED: &str = "snapshot";
+pub const STAKE_CHECKPOINT_SEED: &str = "stake-checkpoint";
+pub const DISTRIBUTION_SEED: &str = "distribution";
+pub const AIRDROP_VAULT_SEED: &str = "airdrop-vault";
+pub const ESCROW_SEED: &str = "escrow";
+pub const TOKEN_VAULT_SEED: &str = "token-vault";
 
+//  Verified creator pNFTs of the collection must have
+#[cfg(not(feature = "localnet"))]
 pub const COLLECTION_ADDRESS: &str = "26WJyhNttQCts4TWRhAeHR51GhtqgVmrMHqWpmHBXmbm";
+#[cfg(feature = "localnet")]
+pub const COLLECTION_ADDRESS: &str = "HpAsMcNFx1V2bbk58cmQrycQLcvhQDz9poH6VY3LDy1k";
+//  Group mint of Token-2022 collection, members point to it by group member extension
+#[cfg(not(feature = "localnet"))]
+pub const TOKEN_2022_COLLECTION_GROUP: &str = "CkybZhH8vV7T1anmhn1VCNb82F5dX46hspx7NTUzU5D";
+#[cfg(feature = "localnet")]
+pub const TOKEN_2022_COLLECTION_GROUP: &str = "D6irtwCDUnqf9j3BmjeeYdokAEP1wJTAP5djLmbXwyup";
+
+//  Metaplex Core collection account of staked assets
+#[cfg(not(feature = "localnet"))]
+pub const CORE_COLLECTION: &str = "9TmWvfu1dK7thXJ7a31x7tdKZH3MCnjMa59nEV1ropZD";
+#[cfg(feature = "localnet")]
+pub const CORE_COLLECTION: &str = "92eKbtCopGnJpESigWg9XC3KmcMcXyCfPBxUcudmjwMw";
+
+pub const MPL_CORE_PROGRAM_ADDRESS: &str = "CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d";
+//  Metaplex Core account key, instruction and plugin tags
+pub const CORE_ASSET_KEY: u8 = 1;
+pub const CORE_ADD_PLUGIN_IX: u8 = 2;
+pub const CORE_UPDATE_PLUGIN_IX: u8 = 6;
+pub const CORE_APPROVE_PLUGIN_AUTHORITY_IX: u8 = 8;
+pub const CORE_REVOKE_PLUGIN_AUTHORITY_IX: u8 = 10;
+pub const CORE_FREEZE_DELEGATE_PLUGIN: u8 = 1;
+pub const CORE_AUTHORITY_ADDRESS: u8 = 3;
+
+//  Collection mint compressed NFTs must be verified members of
+#[cfg(not(feature = "localnet"))]
+pub const CNFT_COLLECTION_MINT: &str = "7bZWCNypBMFrjN4Poa4PtuVpRqK44sWLzS1VAK8u19Rg";
+#[cfg(feature = "localnet")]
+pub const CNFT_COLLECTION_MINT: &str = "GFHXQC7Ur5uLF5RHbepw6ZbCAFSuiNfsSpL29Nz6Q6mG";
+
+pub const BUBBLEGUM_PROGRAM_ADDRESS: &str = "BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY";
+pub const SPL_NOOP_PROGRAM_ADDRESS: &str = "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV";
+pub const SPL_COMPRESSION_PROGRAM_ADDRESS: &str = "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK";
+//  Anchor discriminator of bubblegum transfer instruction
+pub const BUBBLEGUM_TRANSFER_DISCRIMINATOR: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+
+//  Token-2022 mint extensions follow base mint padded to token account size and account type byte
+pub const TOKEN_2022_EXTENSIONS_OFFSET: usize = 165 + 1;
+pub const EXTENSION_NON_TRANSFERABLE: u16 = 9;
+pub const EXTENSION_TOKEN_GROUP_MEMBER: u16 = 23;
 
 pub const MAX_STAKE_AMOUNT: u16 = 50;
 
 pub const DAY_SECONDS: i64 = 60 * 60 * 24;
+
+//  Lock tiers users can commit to, multiplier is in basis points
+pub const MAX_LOCK_TIERS: usize = 4;
+pub const MAX_LOCK_DAYS: u16 = 365;
+pub const BASE_MULTIPLIER: u16 = 10_000;
+pub const MAX_MULTIPLIER: u16 = 5 * BASE_MULTIPLIER;
+pub const DEFAULT_LOCK_TIERS: [(u16, u16); MAX_LOCK_TIERS] =
+    [(0, 10_000), (30, 12_500), (90, 15_000), (180, 20_000)];
+
+//  Streak bonus steps by days of uninterrupted staking, bonus is in basis points
+pub const MAX_STREAK_STEPS: usize = 4;
+pub const MAX_STREAK_BONUS: u16 = BASE_MULTIPLIER;
+
+//  Rarity weight of NFTs when admin hasn't published weight root
+pub const DEFAULT_NFT_WEIGHT: u16 = 1;
+
+//  Reward tokens distributed to stakers at the same time
+pub const MAX_REWARD_STREAMS: usize = 4;
+//  Scale of accumulated reward per base weight
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+//  Max tip paid to crankers from reward vault, in basis points of pushed rewards
+pub const MAX_CRANK_TIP_BPS: u16 = 500;
+
+//  Governance voting power of each staked NFT
+pub const VOTER_WEIGHT_PER_NFT: u64 = 1;
+//  Voted NFTs stay staked this long after a governance vote, must cover max voting time of the realm
+pub const GOVERNANCE_VOTE_LOCK: i64 = 3 * DAY_SECONDS;
+
+//  Options of a staker poll
+pub const MAX_POLL_OPTIONS: usize = 8;
+//  Voters can't unstake until polls end, so polls can't end later than this from creation
+pub const MAX_POLL_DURATION: i64 = 30 * DAY_SECONDS;
+
+//  Min time between permissionless snapshots
+pub const MIN_SNAPSHOT_INTERVAL: i64 = DAY_SECONDS;
+
+//  Claimed bitmap of a distribution must fit in a single account allocation
+pub const MAX_AIRDROP_LEAVES: u32 = 80_000;
+
+//  Programs allowed to spend user points through CPI
+pub const MAX_POINTS_SPENDERS: usize = 4;
+
+//  Marketplace programs allowed to unstake and sell pNFTs through CPI
+pub const MAX_MARKETPLACES: usize = 4;
+
+//  Delay applied to queued config changes until admin updates it
+//  local validator tests apply config changes right away
+#[cfg(not(feature = "localnet"))]
+pub const DEFAULT_CONFIG_DELAY: i64 = 2 * DAY_SECONDS;
+#[cfg(feature = "localnet")]
+pub const DEFAULT_CONFIG_DELAY: i64 = 0;
+
+//  Account layout versions, migrate instructions bring older accounts up to these
+pub const GLOBAL_POOL_VERSION: u8 = 1;
+pub const USER_POOL_VERSION: u8 = 1;
+pub const MAX_CONFIG_DELAY: i64 = 30 * DAY_SECONDS;
+pub const MAX_UNBONDING_PERIOD: i64 = 30 * DAY_SECONDS;
+pub const MAX_VESTING_DURATION: i64 = 365 * DAY_SECONDS;
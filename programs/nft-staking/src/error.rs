@@ -16,4 +16,92 @@ pub enum StakingError {
     NftNotExist,
     #[msg("Can not unlock NFT before time")]
     StillLocked,
+    #[msg("Config value is invalid")]
+    InvalidConfig,
+    #[msg("Config change is still timelocked")]
+    TimelockNotExpired,
+    #[msg("Wallet is blocked")]
+    WalletBlocked,
+    #[msg("NFT mint is blocked")]
+    MintBlocked,
+    #[msg("Treasury can not go below rent exempt minimum")]
+    InsufficientTreasury,
+    #[msg("Unlock must be requested first")]
+    UnbondingRequired,
+    #[msg("Unlock is already requested")]
+    UnlockAlreadyRequested,
+    #[msg("Unlock is not requested")]
+    UnlockNotRequested,
+    #[msg("Lock tier is invalid")]
+    InvalidLockTier,
+    #[msg("Reward stream is invalid")]
+    InvalidRewardStream,
+    #[msg("No reward to claim")]
+    NoReward,
+    #[msg("NFT weight proof is invalid")]
+    InvalidWeightProof,
+    #[msg("Reward vault is empty")]
+    RewardVaultEmpty,
+    #[msg("Not enough points")]
+    InsufficientPoints,
+    #[msg("Caller program is not allowed")]
+    UnauthorizedCaller,
+    #[msg("Store item is not on sale")]
+    SaleNotActive,
+    #[msg("Store item is sold out")]
+    SoldOut,
+    #[msg("Wallet limit of store item reached")]
+    ExceedWalletLimit,
+    #[msg("Token account is not owned by reward recipient")]
+    InvalidRecipient,
+    #[msg("Reward recipient is not set")]
+    NoRewardRecipient,
+    #[msg("Claimed rewards are vesting")]
+    RewardsVesting,
+    #[msg("No vested reward to withdraw")]
+    NoVestedReward,
+    #[msg("No revenue to claim")]
+    NoRevenue,
+    #[msg("Poll is not active")]
+    PollNotActive,
+    #[msg("Invalid poll option")]
+    InvalidPollOption,
+    #[msg("Can't unstake while voted poll or proposal is active")]
+    VoteActive,
+    #[msg("Too early to take snapshot")]
+    SnapshotTooEarly,
+    #[msg("Invalid airdrop proof")]
+    InvalidAirdropProof,
+    #[msg("Airdrop already claimed")]
+    AirdropClaimed,
+    #[msg("Escrow token account is required")]
+    EscrowRequired,
+    #[msg("Stake checkpoint is required")]
+    CheckpointRequired,
+    #[msg("NFT is not held this way")]
+    InvalidCustody,
+    #[msg("Asset is already frozen")]
+    AssetFrozen,
+    #[msg("Amount must be positive")]
+    InvalidAmount,
+    #[msg("Token staking is disabled")]
+    TokenStakingDisabled,
+    #[msg("Insufficient staked token amount")]
+    InsufficientStake,
+    #[msg("Stake mint can't change while tokens are staked")]
+    TokensStaked,
+    #[msg("Receipt token of the position is required")]
+    ReceiptRequired,
+    #[msg("Reward vault doesn't cover unpaid rewards")]
+    UnderfundedObligation,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Non-transferable NFT can't be staked")]
+    NonTransferableMint,
+    #[msg("NFT is not staked by the legacy program")]
+    NotLegacyStake,
+    #[msg("Voter weight action needs a target")]
+    ActionTargetRequired,
+    #[msg("Only pNFTs locked in place can be sold, receipt positions trade through their receipt")]
+    SaleCustodyUnsupported,
 }
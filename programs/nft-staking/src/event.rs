@@ -0,0 +1,33 @@
+use crate::*;
+
+#[event]
+pub struct PointsAdjusted {
+    pub user: Pubkey,
+    pub delta: i64,
+    pub reason_code: u16,
+    pub balance: u64,
+}
+
+#[event]
+pub struct PointsSpent {
+    pub user: Pubkey,
+    pub program: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct StakeSold {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub program: Pubkey,
+}
+
+#[event]
+pub struct ItemRedeemed {
+    pub user: Pubkey,
+    pub item: Pubkey,
+    pub quantity: u32,
+    pub points_spent: u64,
+}
@@ -0,0 +1,42 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct AddBlocklist<'info> {
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
+    //  Blocklist entry of wallet or NFT mint
+    #[account(
+        init,
+        space = 8 + BlocklistEntry::DATA_SIZE,
+        seeds = [BLOCKLIST_SEED.as_ref(), address.as_ref()],
+        bump,
+        payer = admin
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl AddBlocklist<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, address: Pubkey) -> Result<()> {
+        let blocklist_entry = &mut ctx.accounts.blocklist_entry;
+
+        blocklist_entry.address = address;
+        blocklist_entry.blocked_at = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+}
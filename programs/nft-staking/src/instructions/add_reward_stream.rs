@@ -0,0 +1,71 @@
+use {
+    crate::*,
+    anchor_spl::token::{Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct AddRewardStream<'info> {
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    pub reward_mint: Box<Account<'info, Mint>>,
+
+    //  Vault holds reward tokens of this stream, owned by global pool
+    #[account(
+        init,
+        seeds = [REWARD_VAULT_SEED.as_ref(), global_pool.stream_cnt.to_le_bytes().as_ref()],
+        bump,
+        payer = admin,
+        token::mint = reward_mint,
+        token::authority = global_pool,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl AddRewardStream<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        rate: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        let global_pool = &mut ctx.accounts.global_pool;
+
+        require!(
+            (global_pool.stream_cnt as usize) < MAX_REWARD_STREAMS,
+            StakingError::ExceedMaxCount
+        );
+        let timestamp = Clock::get()?.unix_timestamp;
+        require!(
+            start_time >= timestamp && end_time > start_time,
+            StakingError::InvalidRewardStream
+        );
+
+        let index = global_pool.stream_cnt as usize;
+        let stream = &mut global_pool.reward_streams[index];
+        stream.mint = ctx.accounts.reward_mint.key();
+        stream.rate = rate;
+        stream.start_time = start_time;
+        stream.end_time = end_time;
+        stream.last_update_time = timestamp;
+
+        global_pool.stream_cnt += 1;
+
+        Ok(())
+    }
+}
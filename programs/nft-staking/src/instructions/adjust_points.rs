@@ -0,0 +1,54 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct AdjustPoints<'info> {
+    #[account(
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    //  PDA that stores user's points
+    #[account(
+        mut,
+        seeds = [user_pool.user.as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+}
+
+impl AdjustPoints<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, delta: i64, reason_code: u16) -> Result<()> {
+        let global_pool = &mut ctx.accounts.global_pool;
+        let user_pool = &mut ctx.accounts.user_pool;
+
+        user_pool.settle_rewards(global_pool, Clock::get()?.unix_timestamp)?;
+
+        user_pool.points = if delta >= 0 {
+            user_pool
+                .points
+                .checked_add(delta as u64)
+                .ok_or(StakingError::MathOverflow)?
+        } else {
+            user_pool
+                .points
+                .checked_sub(delta.unsigned_abs())
+                .ok_or(StakingError::InsufficientPoints)?
+        };
+
+        emit!(PointsAdjusted {
+            user: user_pool.user,
+            delta,
+            reason_code,
+            balance: user_pool.points,
+        });
+
+        Ok(())
+    }
+}
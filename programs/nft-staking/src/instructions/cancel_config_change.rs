@@ -0,0 +1,34 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    // Current admin
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    //  Global pool stores admin address
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
+    //  Queued change, closed without being applied
+    #[account(
+        mut,
+        seeds = [CONFIG_CHANGE_SEED.as_ref(), pending_change.id.to_le_bytes().as_ref()],
+        bump,
+        close = admin
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+}
+
+impl CancelConfigChange<'_> {
+    pub fn process_instruction(_ctx: &mut Context<Self>) -> Result<()> {
+        // Closing the pending change account drops it
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct CancelUnlock<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Stake info of the unbonding NFT
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), staked_nft.mint.as_ref()],
+        bump,
+        constraint = staked_nft.owner == signer.key() @StakingError::NftNotExist,
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    /// CHECK blocklist entry of signer, must not exist
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), signer.key().as_ref()],
+        bump,
+    )]
+    pub wallet_blocklist: UncheckedAccount<'info>,
+    /// CHECK blocklist entry of staked mint, must not exist
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), staked_nft.mint.as_ref()],
+        bump,
+    )]
+    pub mint_blocklist: UncheckedAccount<'info>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            signer.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = signer
+    )]
+    pub stake_checkpoint: Box<Account<'info, StakeCheckpoint>>,
+
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn cancel_unlock_handler(ctx: Context<CancelUnlock>) -> Result<()> {
+    let staked_nft = &mut ctx.accounts.staked_nft;
+    require!(staked_nft.is_unbonding(), StakingError::UnlockNotRequested);
+
+    // Blocked wallets and mints can't go back to staking
+    require!(
+        ctx.accounts.wallet_blocklist.data_is_empty(),
+        StakingError::WalletBlocked
+    );
+    require!(
+        ctx.accounts.mint_blocklist.data_is_empty(),
+        StakingError::MintBlocked
+    );
+
+    staked_nft.unlock_requested_at = 0;
+
+    // NFT is back in active staking
+    let user_pool = &mut ctx.accounts.user_pool;
+    let global_pool = &mut ctx.accounts.global_pool;
+    let timestamp = Clock::get()?.unix_timestamp;
+    user_pool.settle_rewards(global_pool, timestamp)?;
+    user_pool.checkpoint_stake(global_pool, &mut ctx.accounts.stake_checkpoint);
+    user_pool.add_stake(global_pool, staked_nft.weight, timestamp);
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    #[account(
+        mut,
+        seeds = [POLL_SEED.as_ref(), poll.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub poll: Box<Account<'info, Poll>>,
+
+    //  Vote of user, init fails if user already voted
+    #[account(
+        init,
+        space = 8 + VoteRecord::DATA_SIZE,
+        seeds = [VOTE_RECORD_SEED.as_ref(), poll.key().as_ref(), signer.key().as_ref()],
+        bump,
+        payer = signer
+    )]
+    pub vote_record: Box<Account<'info, VoteRecord>>,
+
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn cast_vote_handler(ctx: Context<CastVote>, option: u8) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let poll = &mut ctx.accounts.poll;
+    require!(
+        timestamp >= poll.start_time && timestamp < poll.end_time,
+        StakingError::PollNotActive
+    );
+    require!(option < poll.option_cnt, StakingError::InvalidPollOption);
+
+    let user_pool = &mut ctx.accounts.user_pool;
+    let weight = user_pool.stake_cnt as u64;
+    require!(weight > 0, StakingError::NftNotExist);
+    poll.votes[option as usize] += weight;
+
+    // Voted NFTs stay staked until the poll ends
+    user_pool.vote_lock_end = user_pool.vote_lock_end.max(poll.end_time);
+
+    let vote_record = &mut ctx.accounts.vote_record;
+    vote_record.poll = poll.key();
+    vote_record.user = user_pool.user;
+    vote_record.option = option;
+    vote_record.weight = weight;
+    vote_record.voted_at = timestamp;
+
+    Ok(())
+}
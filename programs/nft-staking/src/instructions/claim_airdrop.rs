@@ -0,0 +1,81 @@
+use {
+    crate::*,
+    anchor_lang::solana_program::keccak,
+    anchor_spl::token::{Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [DISTRIBUTION_SEED.as_ref(), distribution.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub distribution: Box<Account<'info, Distribution>>,
+
+    //  Vault holds airdrop tokens of the distribution
+    #[account(
+        mut,
+        seeds = [AIRDROP_VAULT_SEED.as_ref(), distribution.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub airdrop_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = distribution.mint,
+        token::authority = signer,
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_airdrop_handler(
+    ctx: Context<ClaimAirdrop>,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let distribution = &mut ctx.accounts.distribution;
+    require!(
+        index < distribution.leaf_cnt,
+        StakingError::InvalidAirdropProof
+    );
+    require!(
+        !distribution.is_claimed(index),
+        StakingError::AirdropClaimed
+    );
+
+    let leaf = keccak::hashv(&[
+        &index.to_le_bytes(),
+        ctx.accounts.signer.key().as_ref(),
+        &amount.to_le_bytes(),
+    ]);
+    require!(
+        verify_merkle_proof(&proof, distribution.root, leaf.0),
+        StakingError::InvalidAirdropProof
+    );
+
+    distribution.set_claimed(index);
+    distribution.claimed_amount += amount;
+
+    transfer_from_vault(
+        ctx.accounts.airdrop_vault.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.global_pool.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        *ctx.bumps.get("global_pool").unwrap(),
+        amount,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use {
+    crate::*,
+    anchor_lang::system_program::{transfer, Transfer},
+};
+
+#[derive(Accounts)]
+pub struct ClaimRevenue<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Revenue vault holds SOL revenue shared with stakers
+    #[account(
+        mut,
+        seeds = [REVENUE_SEED.as_ref()],
+        bump
+    )]
+    pub revenue_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn claim_revenue_handler(ctx: Context<ClaimRevenue>) -> Result<()> {
+    let global_pool = &mut ctx.accounts.global_pool;
+    let user_pool = &mut ctx.accounts.user_pool;
+    user_pool.settle_rewards(global_pool, Clock::get()?.unix_timestamp)?;
+
+    let amount = user_pool.revenue;
+    require!(amount > 0, StakingError::NoRevenue);
+    user_pool.revenue = 0;
+
+    let seeds = &[
+        REVENUE_SEED.as_bytes(),
+        &[*ctx.bumps.get("revenue_vault").unwrap()],
+    ];
+    let signer = &[&seeds[..]];
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.revenue_vault.to_account_info(),
+                to: ctx.accounts.signer.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use {
+    crate::*,
+    anchor_spl::token::{Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+#[instruction(stream_index: u8)]
+pub struct ClaimReward<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Vault of the reward stream
+    #[account(
+        mut,
+        seeds = [REWARD_VAULT_SEED.as_ref(), stream_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    //  Token account of user, or of reward recipient if set
+    #[account(
+        mut,
+        token::mint = reward_vault.mint,
+        constraint = user_token_account.owner == user_pool.reward_destination() @StakingError::InvalidRecipient,
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    //  Vesting schedule of user's rewards of the stream
+    #[account(
+        init_if_needed,
+        space = 8 + Vesting::DATA_SIZE,
+        seeds = [VESTING_SEED.as_ref(), signer.key().as_ref(), stream_index.to_le_bytes().as_ref()],
+        bump,
+        payer = signer
+    )]
+    pub vesting: Box<Account<'info, Vesting>>,
+
+    pub token_program: Program<'info, Token>,
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn claim_reward_handler(ctx: Context<ClaimReward>, stream_index: u8) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let global_pool = &mut ctx.accounts.global_pool;
+    let user_pool = &mut ctx.accounts.user_pool;
+    user_pool.settle_rewards(global_pool, timestamp)?;
+
+    // Rewards vest linearly and are paid out by withdraw_vested
+    if global_pool.vesting_duration > 0 {
+        let amount = user_pool.take_reward_for_vesting(global_pool, stream_index)?;
+
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.user = user_pool.user;
+        vesting.stream_index = stream_index;
+        vesting.add(amount, global_pool.vesting_duration, timestamp);
+
+        return Ok(());
+    }
+
+    let amount =
+        user_pool.take_reward(global_pool, stream_index, ctx.accounts.reward_vault.amount)?;
+
+    transfer_from_vault(
+        ctx.accounts.reward_vault.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        global_pool.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        *ctx.bumps.get("global_pool").unwrap(),
+        amount,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use {
+    crate::*,
+    anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface},
+//    anchor_spl::associated_token::{AssociatedToken}
+};
+
+#[derive(Accounts)]
+pub struct CompleteUnlock<'info> {
+    // Need admin sign to unlock pNFT
+    #[account(
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
+    #[account(
+        mut, 
+        token::mint = token_mint, 
+        token::authority = signer,
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK instruction will fail if wrong edition is supplied
+    pub token_mint_edition: AccountInfo<'info>,
+    /// CHECK instruction will fail if wrong record is supplied
+    #[account(mut)]
+    pub token_mint_record: AccountInfo<'info>,
+    /// CHECK instruction will fail if wrong metadata is supplied
+    #[account(mut)]
+    mint_metadata: UncheckedAccount<'info>,
+    /// CHECK instruction will fail if wrong rules are supplied
+    pub auth_rules: UncheckedAccount<'info>,
+    /// CHECK instruction will fail if wrong sysvar ixns are supplied
+    pub sysvar_instructions: AccountInfo<'info>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Stake info of this NFT, closed on unlock
+    //  stake count was already decreased on request
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+        constraint = staked_nft.owner == signer.key() @StakingError::NftNotExist,
+        close = signer
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Escrow holding Token-2022 NFT that token metadata can't lock
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub escrow_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    //  Treasury collects unlock fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED.as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK intstruction will fail if wrong program is supplied
+    pub token_metadata_program: AccountInfo<'info>,
+    /// CHECK intstruction will fail if wrong program is supplied
+    pub auth_rules_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+//    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> CompleteUnlock<'info> {
+    pub fn pnft_accounts(&self) -> PnftAccounts<'info> {
+        PnftAccounts {
+            delegate: self.user_pool.to_account_info(),
+            token_owner: self.signer.to_account_info(),
+            token_account: self.token_account.to_account_info(),
+            token_mint: self.token_mint.to_account_info(),
+            token_mint_edition: self.token_mint_edition.to_account_info(),
+            token_mint_record: self.token_mint_record.to_account_info(),
+            mint_metadata: self.mint_metadata.to_account_info(),
+            payer: self.signer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            sysvar_instructions: self.sysvar_instructions.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            auth_rules_program: self.auth_rules_program.to_account_info(),
+            auth_rules: self.auth_rules.to_account_info(),
+        }
+    }
+}
+
+pub fn complete_unlock_handler(ctx: Context<CompleteUnlock>) -> Result<()> {
+    // Unlock is allowed only after unbonding period since request
+    let staked_nft = &ctx.accounts.staked_nft;
+    require!(staked_nft.is_unbonding(), StakingError::UnlockNotRequested);
+    staked_nft.check_unlock(
+        &ctx.accounts.global_pool,
+        ctx.accounts.user_pool.vote_lock_end,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    let signer = ctx.accounts.signer.key();
+
+    let seeds = &[
+        signer.as_ref(),
+        USER_POOL_SEED.as_bytes(), 
+        &[*ctx.bumps.get("user_pool").unwrap()]
+    ];
+    let delegate_seeds = &[&seeds[..]];
+    
+    release_nft(
+        ctx.accounts.staked_nft.custody,
+        &ctx.accounts.pnft_accounts(),
+        &ctx.accounts.signer.to_account_info(),
+        ctx.accounts
+            .escrow_token_account
+            .as_ref()
+            .map(|escrow| escrow.to_account_info()),
+        delegate_seeds,
+    )?;
+
+    transfer_fee(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.global_pool.unlock_fee,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use {
+    crate::*,
+    anchor_spl::{
+        associated_token::get_associated_token_address,
+        token::{Token, TokenAccount},
+    },
+};
+
+#[derive(Accounts)]
+#[instruction(stream_index: u8)]
+pub struct CrankClaim<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    pub cranker: Signer<'info>,
+
+    //  PDA that stores stake info of user to claim for
+    #[account(
+        mut,
+        seeds = [user_pool.user.as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Vault of the reward stream
+    #[account(
+        mut,
+        seeds = [REWARD_VAULT_SEED.as_ref(), stream_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    //  Associated token account of reward recipient
+    #[account(
+        mut,
+        constraint = user_pool.reward_recipient.map(|recipient|
+            get_associated_token_address(&recipient, &reward_vault.mint)
+        ) == Some(recipient_token_account.key()) @StakingError::InvalidRecipient,
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    //  Cranker receives the tip here
+    #[account(
+        mut,
+        token::mint = reward_vault.mint,
+    )]
+    pub cranker_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn crank_claim_handler(ctx: Context<CrankClaim>, stream_index: u8) -> Result<()> {
+    let global_pool = &mut ctx.accounts.global_pool;
+    let user_pool = &mut ctx.accounts.user_pool;
+    require!(
+        user_pool.reward_recipient.is_some(),
+        StakingError::NoRewardRecipient
+    );
+    // Claimed rewards can only vest through claim_reward
+    require!(
+        global_pool.vesting_duration == 0,
+        StakingError::RewardsVesting
+    );
+
+    user_pool.settle_rewards(global_pool, Clock::get()?.unix_timestamp)?;
+
+    let available = ctx.accounts.reward_vault.amount;
+    let amount = user_pool.take_reward(global_pool, stream_index, available)?;
+
+    // Tip comes from the pool on top of user rewards, limited by funds not yet emitted
+    let tip = (amount as u128 * global_pool.crank_tip_bps as u128 / BASE_MULTIPLIER as u128) as u64;
+    let stream = &mut global_pool.reward_streams[stream_index as usize];
+    let tip = tip
+        .min(available - amount)
+        .min(stream.funded.saturating_sub(stream.emitted));
+    stream.emitted += tip;
+    stream.claimed += tip;
+
+    let global_bump = *ctx.bumps.get("global_pool").unwrap();
+    transfer_from_vault(
+        ctx.accounts.reward_vault.to_account_info(),
+        ctx.accounts.recipient_token_account.to_account_info(),
+        global_pool.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        global_bump,
+        amount,
+    )?;
+
+    if tip > 0 {
+        transfer_from_vault(
+            ctx.accounts.reward_vault.to_account_info(),
+            ctx.accounts.cranker_token_account.to_account_info(),
+            global_pool.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            global_bump,
+            tip,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use {
+    crate::*,
+    anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+#[derive(Accounts)]
+#[instruction(id: u32, root: [u8; 32], leaf_cnt: u32)]
+pub struct CreateDistribution<'info> {
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        init,
+        space = 8 + Distribution::DATA_SIZE + Distribution::bitmap_size(leaf_cnt),
+        seeds = [DISTRIBUTION_SEED.as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        payer = admin
+    )]
+    pub distribution: Box<Account<'info, Distribution>>,
+
+    pub mint: Box<Account<'info, Mint>>,
+
+    //  Vault holds airdrop tokens, owned by global pool
+    #[account(
+        init,
+        seeds = [AIRDROP_VAULT_SEED.as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        payer = admin,
+        token::mint = mint,
+        token::authority = global_pool,
+    )]
+    pub airdrop_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = admin,
+    )]
+    pub admin_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl CreateDistribution<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        id: u32,
+        root: [u8; 32],
+        leaf_cnt: u32,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            leaf_cnt > 0 && leaf_cnt <= MAX_AIRDROP_LEAVES && amount > 0,
+            StakingError::InvalidConfig
+        );
+
+        let distribution = &mut ctx.accounts.distribution;
+        distribution.id = id;
+        distribution.mint = ctx.accounts.mint.key();
+        distribution.root = root;
+        distribution.total_amount = amount;
+        distribution.leaf_cnt = leaf_cnt;
+        distribution.claimed = vec![0; Distribution::bitmap_size(leaf_cnt)];
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin_token_account.to_account_info(),
+                    to: ctx.accounts.airdrop_vault.to_account_info(),
+                    authority: ctx.accounts.admin.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct CreatePoll<'info> {
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        init,
+        space = 8 + Poll::DATA_SIZE,
+        seeds = [POLL_SEED.as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        payer = admin
+    )]
+    pub poll: Account<'info, Poll>,
+
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl CreatePoll<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        id: u32,
+        option_cnt: u8,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        require!(
+            (2..=MAX_POLL_OPTIONS).contains(&(option_cnt as usize)) && end_time > start_time,
+            StakingError::InvalidConfig
+        );
+        // Long polls would keep voters locked for too long
+        require!(
+            end_time <= Clock::get()?.unix_timestamp + MAX_POLL_DURATION,
+            StakingError::InvalidConfig
+        );
+
+        let poll = &mut ctx.accounts.poll;
+        poll.id = id;
+        poll.option_cnt = option_cnt;
+        poll.start_time = start_time;
+        poll.end_time = end_time;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct CreateStoreItem<'info> {
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        init,
+        space = 8 + StoreItem::DATA_SIZE,
+        seeds = [STORE_ITEM_SEED.as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        payer = admin
+    )]
+    pub store_item: Account<'info, StoreItem>,
+
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl CreateStoreItem<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        id: u32,
+        price: u64,
+        supply: u32,
+        per_wallet_limit: u32,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        require!(
+            price > 0 && supply > 0 && end_time > start_time,
+            StakingError::InvalidConfig
+        );
+
+        let store_item = &mut ctx.accounts.store_item;
+        store_item.id = id;
+        store_item.price = price;
+        store_item.supply = supply;
+        store_item.per_wallet_limit = per_wallet_limit;
+        store_item.start_time = start_time;
+        store_item.end_time = end_time;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct DepositRevenue<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    //  Revenue vault holds SOL revenue shared with stakers
+    #[account(
+        mut,
+        seeds = [REVENUE_SEED.as_ref()],
+        bump
+    )]
+    pub revenue_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn deposit_revenue_handler(ctx: Context<DepositRevenue>, amount: u64) -> Result<()> {
+    ctx.accounts.global_pool.distribute_revenue(amount)?;
+
+    transfer_fee(
+        ctx.accounts.depositor.to_account_info(),
+        ctx.accounts.revenue_vault.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        amount,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    // Current admin
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    //  Global pool stores admin address
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
+    //  Queued change, closed once applied
+    #[account(
+        mut,
+        seeds = [CONFIG_CHANGE_SEED.as_ref(), pending_change.id.to_le_bytes().as_ref()],
+        bump,
+        close = admin
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+}
+
+impl ExecuteConfigChange<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>) -> Result<()> {
+        let timestamp = Clock::get()?.unix_timestamp;
+        ctx.accounts.pending_change.execute(&mut ctx.accounts.global_pool, timestamp)
+    }
+}
@@ -0,0 +1,146 @@
+use {crate::*, std::str::FromStr};
+
+#[derive(Accounts)]
+#[instruction(asset_id: Pubkey)]
+pub struct ForceUnlockCnft<'info> {
+    // Only admin can force unlock
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    //  Only blocked leaves can be force unlocked
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), asset_id.as_ref()],
+        bump,
+    )]
+    pub mint_blocklist: Account<'info, BlocklistEntry>,
+
+    /// CHECK owner of the staked leaf, checked by stake info
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    //  PDA that stores owner's stake info, owns the leaf while staked
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    /// CHECK Tree config of the merkle tree, checked by bubblegum
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK Merkle tree that holds the leaf, checked by bubblegum
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    //  Stake info of this leaf, rent goes back to owner
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), asset_id.as_ref()],
+        bump,
+        constraint = staked_nft.owner == owner.key() @StakingError::NftNotExist,
+        constraint = staked_nft.custody == Custody::CompressedEscrow @StakingError::InvalidCustody,
+        close = owner
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            owner.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = admin
+    )]
+    pub stake_checkpoint: Box<Account<'info, StakeCheckpoint>>,
+
+    /// CHECK SPL noop program
+    #[account(address = Pubkey::from_str(SPL_NOOP_PROGRAM_ADDRESS).unwrap())]
+    pub log_wrapper: UncheckedAccount<'info>,
+    /// CHECK SPL account compression program
+    #[account(address = Pubkey::from_str(SPL_COMPRESSION_PROGRAM_ADDRESS).unwrap())]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK Bubblegum program
+    #[account(address = Pubkey::from_str(BUBBLEGUM_PROGRAM_ADDRESS).unwrap())]
+    pub bubblegum_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ForceUnlockCnft<'info> {
+    pub fn cnft_accounts(&self) -> CnftAccounts<'info> {
+        CnftAccounts {
+            tree_authority: self.tree_authority.to_account_info(),
+            merkle_tree: self.merkle_tree.to_account_info(),
+            log_wrapper: self.log_wrapper.to_account_info(),
+            compression_program: self.compression_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            bubblegum_program: self.bubblegum_program.to_account_info(),
+        }
+    }
+}
+
+/**
+ * Proof nodes of the leaf are passed through remaining accounts
+ */
+pub fn force_unlock_cnft_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, ForceUnlockCnft<'info>>,
+    asset_id: Pubkey,
+    root: [u8; 32],
+    nonce: u64,
+    index: u32,
+    metadata: MetadataArgs,
+) -> Result<()> {
+    require!(
+        asset_id == get_asset_id(&ctx.accounts.merkle_tree.key(), nonce),
+        StakingError::InvalidMetadata
+    );
+
+    let owner = ctx.accounts.owner.key();
+    let seeds = &[
+        owner.as_ref(),
+        USER_POOL_SEED.as_bytes(),
+        &[*ctx.bumps.get("user_pool").unwrap()],
+    ];
+    let owner_seeds = &[&seeds[..]];
+
+    // Return leaf to owner, user pool signs as leaf owner
+    let user_pool = ctx.accounts.user_pool.to_account_info();
+    transfer_cnft(
+        &ctx.accounts.cnft_accounts(),
+        &user_pool,
+        &user_pool,
+        &ctx.accounts.owner.to_account_info(),
+        ctx.remaining_accounts,
+        root,
+        &metadata,
+        nonce,
+        index,
+        owner_seeds,
+    )?;
+
+    if !ctx.accounts.staked_nft.is_unbonding() {
+        // Pending rewards of this leaf are forfeited
+        let user_pool = &mut ctx.accounts.user_pool;
+        let global_pool = &mut ctx.accounts.global_pool;
+        let weight = ctx.accounts.staked_nft.weight;
+        user_pool.settle_rewards_forfeiting(global_pool, Clock::get()?.unix_timestamp, weight)?;
+        user_pool.checkpoint_stake(global_pool, &mut ctx.accounts.stake_checkpoint);
+        user_pool.remove_stake(global_pool, weight)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use {crate::*, std::str::FromStr};
+
+#[derive(Accounts)]
+pub struct ForceUnlockCore<'info> {
+    // Only admin can force unlock
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    //  Only blocked assets can be force unlocked
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), asset.key().as_ref()],
+        bump,
+    )]
+    pub mint_blocklist: Account<'info, BlocklistEntry>,
+
+    /// CHECK owner of the staked asset, checked by stake info
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    //  PDA that stores owner's stake info
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    /// CHECK Core asset frozen by user pool
+    #[account(
+        mut,
+        owner = Pubkey::from_str(MPL_CORE_PROGRAM_ADDRESS).unwrap() @StakingError::InvalidMetadata,
+    )]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK Core collection of the asset
+    #[account(
+        mut,
+        address = Pubkey::from_str(CORE_COLLECTION).unwrap() @StakingError::InvalidCollection,
+    )]
+    pub collection: UncheckedAccount<'info>,
+
+    //  Stake info of this asset, rent goes back to owner
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), asset.key().as_ref()],
+        bump,
+        constraint = staked_nft.owner == owner.key() @StakingError::NftNotExist,
+        constraint = staked_nft.custody == Custody::CoreFreeze @StakingError::InvalidCustody,
+        close = owner
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            owner.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = admin
+    )]
+    pub stake_checkpoint: Box<Account<'info, StakeCheckpoint>>,
+
+    /// CHECK Metaplex Core program
+    #[account(address = Pubkey::from_str(MPL_CORE_PROGRAM_ADDRESS).unwrap())]
+    pub core_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ForceUnlockCore<'info> {
+    pub fn core_accounts(&self) -> CoreAccounts<'info> {
+        CoreAccounts {
+            asset: self.asset.to_account_info(),
+            collection: self.collection.to_account_info(),
+            payer: self.admin.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            core_program: self.core_program.to_account_info(),
+        }
+    }
+}
+
+pub fn force_unlock_core_handler(ctx: Context<ForceUnlockCore>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+
+    let seeds = &[
+        owner.as_ref(),
+        USER_POOL_SEED.as_bytes(),
+        &[*ctx.bumps.get("user_pool").unwrap()],
+    ];
+    let delegate_seeds = &[&seeds[..]];
+
+    // Freeze delegate thaws and revokes itself since owner doesn't sign
+    thaw_core_asset(
+        &ctx.accounts.core_accounts(),
+        &ctx.accounts.user_pool.to_account_info(),
+        delegate_seeds,
+    )?;
+
+    if !ctx.accounts.staked_nft.is_unbonding() {
+        // Pending rewards of this asset are forfeited
+        let user_pool = &mut ctx.accounts.user_pool;
+        let global_pool = &mut ctx.accounts.global_pool;
+        let weight = ctx.accounts.staked_nft.weight;
+        user_pool.settle_rewards_forfeiting(global_pool, Clock::get()?.unix_timestamp, weight)?;
+        user_pool.checkpoint_stake(global_pool, &mut ctx.accounts.stake_checkpoint);
+        user_pool.remove_stake(global_pool, weight)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use {
+    crate::*,
+    anchor_spl::{
+        associated_token::{get_associated_token_address, AssociatedToken},
+        token_interface::{Mint, TokenAccount, TokenInterface},
+    },
+};
+
+#[derive(Accounts)]
+pub struct ForceUnlockPNFT<'info> {
+    // Only admin can force unlock
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
+    //  Only blocked mints can be force unlocked
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub mint_blocklist: Account<'info, BlocklistEntry>,
+
+    #[account(
+        mut, 
+        token::mint = token_mint, 
+        token::authority = owner,
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK instruction will fail if wrong edition is supplied
+    pub token_mint_edition: AccountInfo<'info>,
+    /// CHECK instruction will fail if wrong record is supplied
+    #[account(mut)]
+    pub token_mint_record: AccountInfo<'info>,
+    /// CHECK instruction will fail if wrong metadata is supplied
+    #[account(mut)]
+    mint_metadata: UncheckedAccount<'info>,
+    /// CHECK instruction will fail if wrong rules are supplied
+    pub auth_rules: UncheckedAccount<'info>,
+    /// CHECK instruction will fail if wrong sysvar ixns are supplied
+    pub sysvar_instructions: AccountInfo<'info>,
+    /// CHECK owner of the staked pNFT or holder of its receipt, checked by token account
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    //  PDA that stores owner's stake info
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Stake info of this NFT, rent goes back to owner
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+        constraint = staked_nft.owner == owner.key()
+            || staked_nft.custody == Custody::ReceiptEscrow @StakingError::NftNotExist,
+        close = owner
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    //  receipt positions aren't in stake count and leave it out
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            owner.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = admin
+    )]
+    pub stake_checkpoint: Option<Box<Account<'info, StakeCheckpoint>>>,
+
+    //  Escrow holding Token-2022 NFT that token metadata can't lock
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub escrow_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    //  Receipt token of the position, held by owner
+    #[account(
+        address = staked_nft.receipt_mint @StakingError::ReceiptRequired,
+    )]
+    pub receipt_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    pub receipt_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// CHECK Token account of global pool holding pNFT of receipt position
+    #[account(
+        mut,
+        address = get_associated_token_address(&global_pool.key(), &token_mint.key()),
+    )]
+    pub receipt_escrow: Option<UncheckedAccount<'info>>,
+    /// CHECK instruction will fail if wrong record is supplied
+    #[account(mut)]
+    pub receipt_escrow_record: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK intstruction will fail if wrong program is supplied
+    pub token_metadata_program: AccountInfo<'info>,
+    /// CHECK intstruction will fail if wrong program is supplied
+    pub auth_rules_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+}
+
+impl<'info> ForceUnlockPNFT<'info> {
+    pub fn pnft_accounts(&self) -> PnftAccounts<'info> {
+        PnftAccounts {
+            delegate: self.user_pool.to_account_info(),
+            token_owner: self.owner.to_account_info(),
+            token_account: self.token_account.to_account_info(),
+            token_mint: self.token_mint.to_account_info(),
+            token_mint_edition: self.token_mint_edition.to_account_info(),
+            token_mint_record: self.token_mint_record.to_account_info(),
+            mint_metadata: self.mint_metadata.to_account_info(),
+            payer: self.admin.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            sysvar_instructions: self.sysvar_instructions.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            auth_rules_program: self.auth_rules_program.to_account_info(),
+            auth_rules: self.auth_rules.to_account_info(),
+        }
+    }
+}
+
+pub fn force_unlock_pnft_handler(ctx: Context<ForceUnlockPNFT>) -> Result<()> {
+    if ctx.accounts.staked_nft.custody == Custody::ReceiptEscrow {
+        return force_unlock_receipt_position(ctx);
+    }
+
+    let owner = ctx.accounts.owner.key();
+
+    let seeds = &[
+        owner.as_ref(),
+        USER_POOL_SEED.as_bytes(), 
+        &[*ctx.bumps.get("user_pool").unwrap()]
+    ];
+    let delegate_seeds = &[&seeds[..]];
+
+    // Staking delegate revokes itself since owner doesn't sign
+    release_nft(
+        ctx.accounts.staked_nft.custody,
+        &ctx.accounts.pnft_accounts(),
+        &ctx.accounts.user_pool.to_account_info(),
+        ctx.accounts
+            .escrow_token_account
+            .as_ref()
+            .map(|escrow| escrow.to_account_info()),
+        delegate_seeds,
+    )?;
+
+    if !ctx.accounts.staked_nft.is_unbonding() {
+        // Pending rewards of this NFT are forfeited
+        let user_pool = &mut ctx.accounts.user_pool;
+        let global_pool = &mut ctx.accounts.global_pool;
+        let weight = ctx.accounts.staked_nft.weight;
+        user_pool.settle_rewards_forfeiting(global_pool, Clock::get()?.unix_timestamp, weight)?;
+        user_pool.checkpoint_stake(
+            global_pool,
+            ctx.accounts
+                .stake_checkpoint
+                .as_mut()
+                .ok_or(StakingError::CheckpointRequired)?,
+        );
+        user_pool.remove_stake(global_pool, weight)?;
+    }
+
+    Ok(())
+}
+
+/**
+ * Receipt position goes back to receipt holder, receipt is left without position
+ * receipt mint is unique to the position, so it can't harvest or unlock a later stake of this NFT
+ * global pool owns the escrowed pNFT and signs the transfer
+ */
+fn force_unlock_receipt_position(ctx: Context<ForceUnlockPNFT>) -> Result<()> {
+    let (
+        Some(receipt_mint),
+        Some(receipt_token_account),
+        Some(receipt_escrow),
+        Some(receipt_escrow_record),
+        Some(associated_token_program),
+    ) = (
+        ctx.accounts.receipt_mint.as_ref(),
+        ctx.accounts.receipt_token_account.as_ref(),
+        ctx.accounts.receipt_escrow.as_ref(),
+        ctx.accounts.receipt_escrow_record.as_ref(),
+        ctx.accounts.associated_token_program.as_ref(),
+    ) else {
+        return err!(StakingError::ReceiptRequired);
+    };
+    require!(
+        receipt_token_account.mint == receipt_mint.key()
+            && receipt_token_account.owner == ctx.accounts.owner.key()
+            && receipt_token_account.amount == 1,
+        StakingError::ReceiptRequired
+    );
+
+    let global_seeds = &[
+        GLOBAL_AUTHORITY_SEED.as_bytes(),
+        &[*ctx.bumps.get("global_pool").unwrap()],
+    ];
+    let escrow_accounts = PnftAccounts {
+        delegate: ctx.accounts.global_pool.to_account_info(),
+        token_owner: ctx.accounts.global_pool.to_account_info(),
+        token_account: receipt_escrow.to_account_info(),
+        token_mint: ctx.accounts.token_mint.to_account_info(),
+        token_mint_edition: ctx.accounts.token_mint_edition.to_account_info(),
+        token_mint_record: receipt_escrow_record.to_account_info(),
+        mint_metadata: ctx.accounts.mint_metadata.to_account_info(),
+        payer: ctx.accounts.admin.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions: ctx.accounts.sysvar_instructions.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        auth_rules_program: ctx.accounts.auth_rules_program.to_account_info(),
+        auth_rules: ctx.accounts.auth_rules.to_account_info(),
+    };
+    transfer_pnft(
+        &escrow_accounts,
+        &ctx.accounts.token_account.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.token_mint_record,
+        &associated_token_program.to_account_info(),
+        &[&global_seeds[..]],
+    )?;
+
+    // Pending rewards of the position are forfeited
+    ctx.accounts
+        .staked_nft
+        .forfeit_position(&mut ctx.accounts.global_pool, Clock::get()?.unix_timestamp);
+
+    Ok(())
+}
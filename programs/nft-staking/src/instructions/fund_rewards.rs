@@ -0,0 +1,96 @@
+use {
+    crate::*,
+    anchor_spl::token::{self, Token, TokenAccount, Transfer},
+};
+
+#[derive(Accounts)]
+#[instruction(stream_index: u8)]
+pub struct FundRewards<'info> {
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    //  Vault of the reward stream
+    #[account(
+        mut,
+        seeds = [REWARD_VAULT_SEED.as_ref(), stream_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = reward_vault.mint,
+        token::authority = admin,
+    )]
+    pub admin_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl FundRewards<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        stream_index: u8,
+        amount: u64,
+        end_time: i64,
+    ) -> Result<()> {
+        let global_pool = &mut ctx.accounts.global_pool;
+        require!(
+            stream_index < global_pool.stream_cnt,
+            StakingError::InvalidRewardStream
+        );
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let stream = &global_pool.reward_streams[stream_index as usize];
+        // Emission can only be extended
+        require!(
+            end_time >= stream.end_time && end_time > timestamp,
+            StakingError::InvalidRewardStream
+        );
+
+        // Emission so far is limited by old funds and end time
+        global_pool.update_rewards(timestamp);
+
+        // Extended campaign must not leave rewards already owed to users unpaid
+        let stream = &mut global_pool.reward_streams[stream_index as usize];
+        let vault_amount = ctx
+            .accounts
+            .reward_vault
+            .amount
+            .checked_add(amount)
+            .ok_or(StakingError::MathOverflow)?;
+        require!(
+            vault_amount >= stream.obligation(),
+            StakingError::UnderfundedObligation
+        );
+        stream.funded = stream
+            .funded
+            .checked_add(amount)
+            .ok_or(StakingError::MathOverflow)?;
+        stream.end_time = end_time;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin_token_account.to_account_info(),
+                    to: ctx.accounts.reward_vault.to_account_info(),
+                    authority: ctx.accounts.admin.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use {
+    crate::*,
+    anchor_spl::token_interface::TokenAccount,
+};
+
+#[derive(Accounts)]
+pub struct HarvestReceipt<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    pub signer: Signer<'info>,
+
+    //  PDA that stores holder's stake info, receives the rewards
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Stake info of the receipt position
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), staked_nft.mint.as_ref()],
+        bump,
+        constraint = staked_nft.custody == Custody::ReceiptEscrow @StakingError::InvalidCustody,
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    /// CHECK Receipt mint of the position
+    #[account(
+        address = staked_nft.receipt_mint @StakingError::ReceiptRequired,
+    )]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    #[account(
+        token::mint = receipt_mint,
+        token::authority = signer,
+        constraint = receipt_token_account.amount == 1 @StakingError::ReceiptRequired,
+    )]
+    pub receipt_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+pub fn harvest_receipt_handler(ctx: Context<HarvestReceipt>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    ctx.accounts.staked_nft.harvest_position(
+        &mut ctx.accounts.global_pool,
+        &mut ctx.accounts.user_pool,
+        timestamp,
+    );
+
+    Ok(())
+}
@@ -5,6 +5,12 @@ pub struct InitUser<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
     //  User pool stores user's stake info
     #[account(
         init,
@@ -25,6 +31,10 @@ impl InitUser<'_> {
         let user_pool = &mut ctx.accounts.user_pool;
 
         user_pool.user = ctx.accounts.user.key();
+        user_pool.points_index = ctx.accounts.global_pool.points_per_nft;
+        user_pool.revenue_index = ctx.accounts.global_pool.revenue_per_nft;
+        user_pool.checkpoint_epoch = ctx.accounts.global_pool.epoch;
+        user_pool.version = USER_POOL_VERSION;
         Ok(())
     }
 }
@@ -1,4 +1,7 @@
-use crate::*;
+use {
+    crate::*,
+    anchor_lang::system_program::{transfer, Transfer},
+};
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
@@ -15,6 +18,22 @@ pub struct Initialize<'info> {
     )]
     pub global_pool: Account<'info, GlobalPool>,
 
+    //  Treasury collects lock and unlock fees
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED.as_ref()],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    //  Revenue vault holds SOL revenue shared with stakers
+    #[account(
+        mut,
+        seeds = [REVENUE_SEED.as_ref()],
+        bump
+    )]
+    pub revenue_vault: SystemAccount<'info>,
+
     //  Needed to init new account
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -25,6 +44,32 @@ impl Initialize<'_> {
         let global_pool = &mut ctx.accounts.global_pool;
 
         global_pool.admin = ctx.accounts.admin.key();
+        global_pool.version = GLOBAL_POOL_VERSION;
+        global_pool.config_delay = DEFAULT_CONFIG_DELAY;
+        global_pool.points_updated_at = Clock::get()?.unix_timestamp;
+        for (tier, (duration_days, multiplier_bps)) in
+            global_pool.lock_tiers.iter_mut().zip(DEFAULT_LOCK_TIERS)
+        {
+            tier.duration_days = duration_days;
+            tier.multiplier_bps = multiplier_bps;
+        }
+
+        // Fund treasury and revenue vault up to rent exempt minimum so small amounts can land in them
+        let rent_exempt = ctx.accounts.rent.minimum_balance(0);
+        for vault in [&ctx.accounts.treasury, &ctx.accounts.revenue_vault] {
+            if vault.lamports() < rent_exempt {
+                transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.admin.to_account_info(),
+                            to: vault.to_account_info(),
+                        },
+                    ),
+                    rent_exempt - vault.lamports(),
+                )?;
+            }
+        }
 
         Ok(())
     }
@@ -0,0 +1,192 @@
+use {crate::*, std::str::FromStr};
+
+#[derive(Accounts)]
+#[instruction(asset_id: Pubkey)]
+pub struct LockCnft<'info> {
+    // Need admin sign to lock compressed NFT
+    #[account(
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info, becomes leaf owner while staked
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    /// CHECK Tree config of the merkle tree, checked by bubblegum
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK Merkle tree that holds the leaf, checked by bubblegum
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    //  PDA that stores stake info of this leaf
+    #[account(
+        init,
+        space = 8 + StakedNft::DATA_SIZE,
+        seeds = [STAKED_NFT_SEED.as_ref(), asset_id.as_ref()],
+        bump,
+        payer = signer
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            signer.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = signer
+    )]
+    pub stake_checkpoint: Box<Account<'info, StakeCheckpoint>>,
+
+    //  Treasury collects lock fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED.as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK blocklist entry of signer, must not exist
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), signer.key().as_ref()],
+        bump,
+    )]
+    pub wallet_blocklist: UncheckedAccount<'info>,
+    /// CHECK blocklist entry of leaf, must not exist
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), asset_id.as_ref()],
+        bump,
+    )]
+    pub mint_blocklist: UncheckedAccount<'info>,
+
+    /// CHECK SPL noop program
+    #[account(address = Pubkey::from_str(SPL_NOOP_PROGRAM_ADDRESS).unwrap())]
+    pub log_wrapper: UncheckedAccount<'info>,
+    /// CHECK SPL account compression program
+    #[account(address = Pubkey::from_str(SPL_COMPRESSION_PROGRAM_ADDRESS).unwrap())]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK Bubblegum program
+    #[account(address = Pubkey::from_str(BUBBLEGUM_PROGRAM_ADDRESS).unwrap())]
+    pub bubblegum_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> LockCnft<'info> {
+    pub fn cnft_accounts(&self) -> CnftAccounts<'info> {
+        CnftAccounts {
+            tree_authority: self.tree_authority.to_account_info(),
+            merkle_tree: self.merkle_tree.to_account_info(),
+            log_wrapper: self.log_wrapper.to_account_info(),
+            compression_program: self.compression_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            bubblegum_program: self.bubblegum_program.to_account_info(),
+        }
+    }
+}
+
+/**
+ * Proof nodes of the leaf are passed through remaining accounts
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn lock_cnft_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, LockCnft<'info>>,
+    asset_id: Pubkey,
+    root: [u8; 32],
+    nonce: u64,
+    index: u32,
+    metadata: MetadataArgs,
+    tier: u8,
+    weight: u16,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    require!(
+        asset_id == get_asset_id(&ctx.accounts.merkle_tree.key(), nonce),
+        StakingError::InvalidMetadata
+    );
+
+    let global_pool = &ctx.accounts.global_pool;
+    let lock_tier = global_pool.lock_tier(tier)?;
+    let rarity_weight = global_pool.rarity_weight(&asset_id, weight, &proof)?;
+
+    // Blocked wallets and leaves can not be staked
+    require!(
+        ctx.accounts.wallet_blocklist.data_is_empty(),
+        StakingError::WalletBlocked
+    );
+    require!(
+        ctx.accounts.mint_blocklist.data_is_empty(),
+        StakingError::MintBlocked
+    );
+
+    // Leaf must be verified member of the collection, the leaf hash binds metadata to the tree
+    let collection = metadata
+        .collection
+        .as_ref()
+        .ok_or(StakingError::InvalidCollection)?;
+    require!(
+        collection.verified && collection.key == Pubkey::from_str(CNFT_COLLECTION_MINT).unwrap(),
+        StakingError::InvalidCollection
+    );
+
+    // Move leaf to user pool, bubblegum verifies ownership with the proof
+    let signer = ctx.accounts.signer.to_account_info();
+    transfer_cnft(
+        &ctx.accounts.cnft_accounts(),
+        &signer,
+        &signer,
+        &ctx.accounts.user_pool.to_account_info(),
+        ctx.remaining_accounts,
+        root,
+        &metadata,
+        nonce,
+        index,
+        &[],
+    )?;
+
+    transfer_fee(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.global_pool.lock_fee,
+    )?;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    let user_pool = &mut ctx.accounts.user_pool;
+    let global_pool = &mut ctx.accounts.global_pool;
+    user_pool.settle_rewards(global_pool, timestamp)?;
+
+    let staked_nft = &mut ctx.accounts.staked_nft;
+    staked_nft.stake(
+        ctx.accounts.signer.key(),
+        asset_id,
+        lock_tier,
+        rarity_weight,
+        Custody::CompressedEscrow,
+        timestamp,
+    );
+
+    user_pool.checkpoint_stake(global_pool, &mut ctx.accounts.stake_checkpoint);
+    user_pool.add_stake(global_pool, staked_nft.weight, timestamp);
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use {crate::*, std::str::FromStr};
+
+#[derive(Accounts)]
+pub struct LockCore<'info> {
+    // Need admin sign to lock asset
+    #[account(
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    /// CHECK Core asset, parsed in instruction
+    #[account(
+        mut,
+        owner = Pubkey::from_str(MPL_CORE_PROGRAM_ADDRESS).unwrap() @StakingError::InvalidMetadata,
+    )]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK Core collection of the asset
+    #[account(
+        mut,
+        address = Pubkey::from_str(CORE_COLLECTION).unwrap() @StakingError::InvalidCollection,
+    )]
+    pub collection: UncheckedAccount<'info>,
+
+    //  PDA that stores stake info of this asset
+    #[account(
+        init,
+        space = 8 + StakedNft::DATA_SIZE,
+        seeds = [STAKED_NFT_SEED.as_ref(), asset.key().as_ref()],
+        bump,
+        payer = signer
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            signer.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = signer
+    )]
+    pub stake_checkpoint: Box<Account<'info, StakeCheckpoint>>,
+
+    //  Treasury collects lock fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED.as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK blocklist entry of signer, must not exist
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), signer.key().as_ref()],
+        bump,
+    )]
+    pub wallet_blocklist: UncheckedAccount<'info>,
+    /// CHECK blocklist entry of asset, must not exist
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), asset.key().as_ref()],
+        bump,
+    )]
+    pub mint_blocklist: UncheckedAccount<'info>,
+
+    /// CHECK Metaplex Core program
+    #[account(address = Pubkey::from_str(MPL_CORE_PROGRAM_ADDRESS).unwrap())]
+    pub core_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> LockCore<'info> {
+    pub fn core_accounts(&self) -> CoreAccounts<'info> {
+        CoreAccounts {
+            asset: self.asset.to_account_info(),
+            collection: self.collection.to_account_info(),
+            payer: self.signer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            core_program: self.core_program.to_account_info(),
+        }
+    }
+}
+
+pub fn lock_core_handler(
+    ctx: Context<LockCore>,
+    tier: u8,
+    weight: u16,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let global_pool = &ctx.accounts.global_pool;
+    let lock_tier = global_pool.lock_tier(tier)?;
+    let rarity_weight = global_pool.rarity_weight(&ctx.accounts.asset.key(), weight, &proof)?;
+
+    // Blocked wallets and assets can not be staked
+    require!(
+        ctx.accounts.wallet_blocklist.data_is_empty(),
+        StakingError::WalletBlocked
+    );
+    require!(
+        ctx.accounts.mint_blocklist.data_is_empty(),
+        StakingError::MintBlocked
+    );
+
+    // Asset must belong to signer and the collection
+    let signer = ctx.accounts.signer.key();
+    let asset = parse_core_asset(&ctx.accounts.asset.try_borrow_data()?)?;
+    require!(asset.owner == signer, StakingError::NftNotExist);
+    require!(
+        asset.collection() == Some(ctx.accounts.collection.key()),
+        StakingError::InvalidCollection
+    );
+
+    let seeds = &[
+        signer.as_ref(),
+        USER_POOL_SEED.as_bytes(),
+        &[*ctx.bumps.get("user_pool").unwrap()],
+    ];
+    let delegate_seeds = &[&seeds[..]];
+
+    freeze_core_asset(
+        &ctx.accounts.core_accounts(),
+        &asset,
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.user_pool.to_account_info(),
+        delegate_seeds,
+    )?;
+
+    transfer_fee(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.global_pool.lock_fee,
+    )?;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    let user_pool = &mut ctx.accounts.user_pool;
+    let global_pool = &mut ctx.accounts.global_pool;
+    user_pool.settle_rewards(global_pool, timestamp)?;
+
+    let staked_nft = &mut ctx.accounts.staked_nft;
+    staked_nft.stake(
+        signer,
+        ctx.accounts.asset.key(),
+        lock_tier,
+        rarity_weight,
+        Custody::CoreFreeze,
+        timestamp,
+    );
+
+    user_pool.checkpoint_stake(global_pool, &mut ctx.accounts.stake_checkpoint);
+    user_pool.add_stake(global_pool, staked_nft.weight, timestamp);
+
+    Ok(())
+}
@@ -1,15 +1,10 @@
 use {
     crate::*,
-    anchor_lang::{
-        solana_program::program::invoke_signed,
-        solana_program::program::invoke, 
-        solana_program::instruction::Instruction
+    mpl_token_metadata::state::{Metadata, TokenMetadataAccount},
+    anchor_spl::{
+        associated_token::{get_associated_token_address, AssociatedToken},
+        token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface},
     },
-    mpl_token_metadata::{
-        state::{Metadata, TokenMetadataAccount},
-        instruction::{MetadataInstruction, DelegateArgs, LockArgs}
-    },
-    anchor_spl::token::{Mint, Token, TokenAccount}
 };
 
 #[derive(Accounts)]
@@ -21,18 +16,19 @@ pub struct LockPNFT<'info> {
     pub admin: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
         bump,
     )]
-    pub global_pool: Account<'info, GlobalPool>,
+    pub global_pool: Box<Account<'info, GlobalPool>>,
 
     #[account(
         mut, 
         token::mint = token_mint, 
         token::authority = signer,
     )]
-    pub token_account: Box<Account<'info, TokenAccount>>,
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
     /// CHECK instruction will fail if wrong edition is supplied
     pub token_mint_edition: AccountInfo<'info>,
     /// CHECK instruction will fail if wrong record is supplied
@@ -56,34 +52,183 @@ pub struct LockPNFT<'info> {
     )]
     pub user_pool: Box<Account<'info, UserPool>>,
 
-    token_program: Program<'info, Token>,
+    //  PDA that stores stake info of this NFT
+    #[account(
+        init,
+        space = 8 + StakedNft::DATA_SIZE,
+        seeds = [STAKED_NFT_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+        payer = signer
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    //  receipt positions aren't in stake count and leave it out
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            signer.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = signer
+    )]
+    pub stake_checkpoint: Option<Box<Account<'info, StakeCheckpoint>>>,
+
+    //  Escrow for Token-2022 NFT that token metadata can't lock, owned by user pool
+    //  must be omitted for other NFTs so no empty escrow is left behind
+    #[account(
+        init_if_needed,
+        seeds = [ESCROW_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+        payer = signer,
+        token::mint = token_mint,
+        token::authority = user_pool,
+    )]
+    pub escrow_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    //  Receipt token of the position in receipt mode, minted by global pool
+    //  new keypair for every position, so a receipt never owns a later position of the same NFT
+    #[account(
+        init,
+        payer = signer,
+        mint::decimals = 0,
+        mint::authority = global_pool,
+    )]
+    pub receipt_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = signer,
+    )]
+    pub receipt_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// CHECK Token account of global pool holding pNFT in receipt mode, created by token metadata
+    #[account(
+        mut,
+        address = get_associated_token_address(&global_pool.key(), &token_mint.key()),
+    )]
+    pub receipt_escrow: Option<UncheckedAccount<'info>>,
+    /// CHECK instruction will fail if wrong record is supplied
+    #[account(mut)]
+    pub receipt_escrow_record: Option<UncheckedAccount<'info>>,
+
+    //  Treasury collects lock fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED.as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK blocklist entry of signer, must not exist
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), signer.key().as_ref()],
+        bump,
+    )]
+    pub wallet_blocklist: UncheckedAccount<'info>,
+    /// CHECK blocklist entry of token mint, must not exist
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub mint_blocklist: UncheckedAccount<'info>,
+
+    token_program: Interface<'info, TokenInterface>,
     /// CHECK intstruction will fail if wrong program is supplied
     token_metadata_program: AccountInfo<'info>,
     /// CHECK intstruction will fail if wrong program is supplied
     auth_rules_program: AccountInfo<'info>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
     pub system_program: Program<'info, System>
 }
 
-pub fn lock_pnft_handler(ctx: Context<LockPNFT>) -> Result<()> {
-    let user_pool = &mut ctx.accounts.user_pool;
+impl<'info> LockPNFT<'info> {
+    pub fn pnft_accounts(&self) -> PnftAccounts<'info> {
+        PnftAccounts {
+            delegate: self.user_pool.to_account_info(),
+            token_owner: self.signer.to_account_info(),
+            token_account: self.token_account.to_account_info(),
+            token_mint: self.token_mint.to_account_info(),
+            token_mint_edition: self.token_mint_edition.to_account_info(),
+            token_mint_record: self.token_mint_record.to_account_info(),
+            mint_metadata: self.mint_metadata.to_account_info(),
+            payer: self.signer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            sysvar_instructions: self.sysvar_instructions.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            auth_rules_program: self.auth_rules_program.to_account_info(),
+            auth_rules: self.auth_rules.to_account_info(),
+        }
+    }
+}
+
+pub fn lock_pnft_handler(
+    ctx: Context<LockPNFT>,
+    tier: u8,
+    weight: u16,
+    proof: Vec<[u8; 32]>,
+    receipt: bool,
+) -> Result<()> {
+    let global_pool = &ctx.accounts.global_pool;
+    let lock_tier = global_pool.lock_tier(tier)?;
+    let rarity_weight =
+        global_pool.rarity_weight(&ctx.accounts.token_mint.key(), weight, &proof)?;
+
+    // Only Token-2022 NFTs are escrowed and only receipt mode mints a receipt
+    require!(
+        ctx.accounts.escrow_token_account.is_none()
+            || ctx.accounts.token_program.key() == anchor_spl::token_2022::ID,
+        StakingError::InvalidCustody
+    );
+    require!(
+        receipt || ctx.accounts.receipt_mint.is_none(),
+        StakingError::InvalidCustody
+    );
+
+    // Blocked wallets and mints can not be staked
+    require!(
+        ctx.accounts.wallet_blocklist.data_is_empty(),
+        StakingError::WalletBlocked
+    );
+    require!(
+        ctx.accounts.mint_blocklist.data_is_empty(),
+        StakingError::MintBlocked
+    );
 
     // Verify metadata is legit
     let mint_metadata = &ctx.accounts.mint_metadata;
-    let nft_metadata = Metadata::from_account_info(mint_metadata)?;
-    
-    // Check if this NFT is the wanted collection and verified
-    if let Some(creators) = nft_metadata.data.creators {
-        let mut valid: u8 = 0;
-        for creator in creators {
-            if creator.address.to_string() == COLLECTION_ADDRESS {
-                valid = 1;
-                break;
-            }
-        }
-        require!(valid == 1, StakingError::InvalidCollection);
+    if mint_metadata.data_is_empty() {
+        // Token-2022 NFT without Metaplex metadata proves collection by its extensions
+        verify_token_2022_collection(&ctx.accounts.token_mint.to_account_info())?;
     } else {
-        return Err(error!(StakingError::MetadataCreatorParseError));
-    };
+        // Metadata must be the token metadata account of this mint
+        require!(
+            *mint_metadata.owner == mpl_token_metadata::ID,
+            StakingError::InvalidMetadata
+        );
+        let nft_metadata = Metadata::from_account_info(mint_metadata)?;
+        require!(
+            nft_metadata.mint == ctx.accounts.token_mint.key(),
+            StakingError::InvalidMetadata
+        );
+
+        // Check if this NFT is the wanted collection and verified
+        if let Some(creators) = nft_metadata.data.creators {
+            let mut valid: u8 = 0;
+            for creator in creators {
+                if creator.verified && creator.address.to_string() == COLLECTION_ADDRESS {
+                    valid = 1;
+                    break;
+                }
+            }
+            require!(valid == 1, StakingError::InvalidCollection);
+        } else {
+            return Err(error!(StakingError::MetadataCreatorParseError));
+        };
+    }
     
     let signer = ctx.accounts.signer.key();
     let seeds = &[
@@ -93,113 +238,122 @@ pub fn lock_pnft_handler(ctx: Context<LockPNFT>) -> Result<()> {
     ];
     let delegate_seeds = &[&seeds[..]];
 
-    invoke(
-        &Instruction {
-            program_id: mpl_token_metadata::id(),
-            accounts: vec![
-                // 0. `[writable]` Delegate record account
-                AccountMeta::new_readonly(mpl_token_metadata::id(), false),
-                // 1. `[]` Delegated owner
-                AccountMeta::new_readonly(user_pool.key(), false),
-                // 2. `[writable]` Metadata account
-                AccountMeta::new(ctx.accounts.mint_metadata.key(), false),
-                // 3. `[optional]` Master Edition account
-                AccountMeta::new_readonly(ctx.accounts.token_mint_edition.key(), false),
-                // 4. `[]` Token record
-                AccountMeta::new(ctx.accounts.token_mint_record.key(), false),
-                // 5. `[]` Mint account
-                AccountMeta::new_readonly(ctx.accounts.token_mint.key(), false),
-                // 6. `[optional, writable]` Token account
-                AccountMeta::new(ctx.accounts.token_account.key(), false),
-                // 7. `[signer]` Approver (update authority or token owner) to approve the delegation
-                AccountMeta::new_readonly(ctx.accounts.signer.key(), true),
-                // 8. `[signer, writable]` Payer
-                AccountMeta::new(ctx.accounts.signer.key(), true),
-                // 9. `[]` System Program
-                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
-                // 10. `[]` Instructions sysvar account
-                AccountMeta::new_readonly(ctx.accounts.sysvar_instructions.key(), false),
-                // 11. `[optional]` SPL Token Program
-                AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
-                // 12. `[optional]` Token Authorization Rules program
-                AccountMeta::new_readonly(ctx.accounts.auth_rules_program.key(), false),
-                // 13. `[optional]` Token Authorization Rules account
-                AccountMeta::new_readonly(ctx.accounts.auth_rules.key(), false),
-            ],
-            data: MetadataInstruction::Delegate(DelegateArgs::StakingV1 {
-                amount: 1,
-                authorization_data: None,
-            })
-            .try_to_vec()
-            .unwrap(),
-        },
-        &[
-            user_pool.to_account_info(),
-            ctx.accounts.mint_metadata.to_account_info(),
-            ctx.accounts.token_mint_edition.to_account_info(),
-            ctx.accounts.token_mint_record.to_account_info(),
-            ctx.accounts.token_mint.to_account_info(),
-            ctx.accounts.token_account.to_account_info(),
-            ctx.accounts.signer.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-            ctx.accounts.sysvar_instructions.to_account_info(),
-            ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.auth_rules_program.to_account_info(),
-            ctx.accounts.auth_rules.to_account_info(),
-        ],
-    )?;
+    // Token metadata can't lock Token-2022 NFTs
+    let custody = if receipt {
+        // Receipt mode moves pNFT to global pool so the position can change hands
+        require!(
+            ctx.accounts.token_program.key() == anchor_spl::token::ID
+                && !ctx.accounts.mint_metadata.data_is_empty(),
+            StakingError::InvalidCustody
+        );
+        let (
+            Some(receipt_mint),
+            Some(receipt_token_account),
+            Some(receipt_escrow),
+            Some(receipt_escrow_record),
+            Some(associated_token_program),
+        ) = (
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_token_account.as_ref(),
+            ctx.accounts.receipt_escrow.as_ref(),
+            ctx.accounts.receipt_escrow_record.as_ref(),
+            ctx.accounts.associated_token_program.as_ref(),
+        ) else {
+            return err!(StakingError::ReceiptRequired);
+        };
 
-    invoke_signed(
-        &Instruction {
-            program_id: mpl_token_metadata::id(),
-            accounts: vec![
-                // 0. `[signer]` Delegate
-                AccountMeta::new_readonly(user_pool.key(), true),
-                // 1. `[optional]` Token owner
-                AccountMeta::new_readonly(ctx.accounts.signer.key(), false),
-                // 2. `[mut]` Token account
-                AccountMeta::new(ctx.accounts.token_account.key(), false),
-                // 3. `[]` Mint account
-                AccountMeta::new_readonly(ctx.accounts.token_mint.key(), false),
-                // 4. `[mut]` Metadata account
-                AccountMeta::new(ctx.accounts.mint_metadata.key(), false),
-                // 5. `[optional]` Edition account
-                AccountMeta::new_readonly(ctx.accounts.token_mint_edition.key(), false),
-                // 6. `[optional, mut]` Token record account
-                AccountMeta::new(ctx.accounts.token_mint_record.key(), false),
-                // 7. `[signer, mut]` Payer
-                AccountMeta::new(ctx.accounts.signer.key(), true),
-                // 8. `[]` System Program
-                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
-                // 9. `[]` Instructions sysvar account
-                AccountMeta::new_readonly(ctx.accounts.sysvar_instructions.key(), false),
-                // 10. `[optional]` SPL Token Program
-                AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
-                // 11. `[optional]` Token Authorization Rules program
-                AccountMeta::new_readonly(ctx.accounts.auth_rules_program.key(), false),
-                // 12. `[optional]` Token Authorization Rules account
-                AccountMeta::new_readonly(ctx.accounts.auth_rules.key(), false),
-            ],
-            data: MetadataInstruction::Lock(LockArgs::V1 { authorization_data: None }).try_to_vec().unwrap(),
-        },
-        &[
-            user_pool.to_account_info(),
-            ctx.accounts.signer.to_account_info(),
+        transfer_pnft(
+            &ctx.accounts.pnft_accounts(),
+            &receipt_escrow.to_account_info(),
+            &ctx.accounts.global_pool.to_account_info(),
+            &receipt_escrow_record.to_account_info(),
+            &associated_token_program.to_account_info(),
+            &[],
+        )?;
+
+        let global_seeds = &[
+            GLOBAL_AUTHORITY_SEED.as_bytes(),
+            &[*ctx.bumps.get("global_pool").unwrap()],
+        ];
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: receipt_mint.to_account_info(),
+                    to: receipt_token_account.to_account_info(),
+                    authority: ctx.accounts.global_pool.to_account_info(),
+                },
+                &[&global_seeds[..]],
+            ),
+            1,
+        )?;
+        Custody::ReceiptEscrow
+    } else if ctx.accounts.token_program.key() == anchor_spl::token_2022::ID {
+        // Non-transferable NFT can't be escrowed and could be burnt while staked
+        let non_transferable = find_mint_extension(
+            &ctx.accounts.token_mint.to_account_info().try_borrow_data()?,
+            EXTENSION_NON_TRANSFERABLE,
+        )
+        .is_some();
+        require!(!non_transferable, StakingError::NonTransferableMint);
+
+        let escrow = ctx
+            .accounts
+            .escrow_token_account
+            .as_ref()
+            .ok_or(StakingError::EscrowRequired)?;
+        transfer_nft(
             ctx.accounts.token_account.to_account_info(),
+            escrow.to_account_info(),
             ctx.accounts.token_mint.to_account_info(),
-            ctx.accounts.mint_metadata.to_account_info(),
-            ctx.accounts.token_mint_edition.to_account_info(),
-            ctx.accounts.token_mint_record.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-            ctx.accounts.sysvar_instructions.to_account_info(),
+            ctx.accounts.signer.to_account_info(),
             ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.auth_rules_program.to_account_info(),
-            ctx.accounts.auth_rules.to_account_info(),
-        ],
-        delegate_seeds,
+            &[],
+        )?;
+        Custody::Escrow
+    } else {
+        delegate_and_lock_pnft(&ctx.accounts.pnft_accounts(), delegate_seeds)?;
+        Custody::TokenMetadataLock
+    };
+
+    transfer_fee(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.global_pool.lock_fee,
     )?;
 
-    user_pool.stake_cnt += 1;
+    let timestamp = Clock::get()?.unix_timestamp;
+    let user_pool = &mut ctx.accounts.user_pool;
+    let global_pool = &mut ctx.accounts.global_pool;
+    let staked_nft = &mut ctx.accounts.staked_nft;
+    staked_nft.stake(
+        signer,
+        ctx.accounts.token_mint.key(),
+        lock_tier,
+        rarity_weight,
+        custody,
+        timestamp,
+    );
+
+    // Receipt position earns on its own, user pool of the staker is left untouched
+    if let (Custody::ReceiptEscrow, Some(receipt_mint)) =
+        (custody, ctx.accounts.receipt_mint.as_ref())
+    {
+        staked_nft.receipt_mint = receipt_mint.key();
+        staked_nft.open_position(global_pool, timestamp);
+        return Ok(());
+    }
+
+    user_pool.settle_rewards(global_pool, timestamp)?;
+    user_pool.checkpoint_stake(
+        global_pool,
+        ctx.accounts
+            .stake_checkpoint
+            .as_mut()
+            .ok_or(StakingError::CheckpointRequired)?,
+    );
+    user_pool.add_stake(global_pool, staked_nft.weight, timestamp);
 
     Ok(())
 
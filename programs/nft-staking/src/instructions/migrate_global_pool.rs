@@ -0,0 +1,59 @@
+use {crate::*, anchor_lang::Discriminator};
+
+#[derive(Accounts)]
+pub struct MigrateGlobalPool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK older layout doesn't deserialize, checked and grown in instruction
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateGlobalPool<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>) -> Result<()> {
+        let global_info = ctx.accounts.global_pool.to_account_info();
+
+        // Admin is the first field of every layout
+        require!(
+            global_info.try_borrow_data()?.get(8..40) == Some(ctx.accounts.admin.key.as_ref()),
+            StakingError::InvalidAdmin
+        );
+        grow_account(
+            &global_info,
+            GlobalPool::DISCRIMINATOR,
+            8 + GlobalPool::DATA_SIZE,
+            ctx.accounts.admin.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        let mut global_pool: Account<GlobalPool> = Account::try_from(&global_info)?;
+        if global_pool.version == 0 {
+            // Zeroed config of fields added since the account was created gets initialize defaults
+            if global_pool.config_delay == 0 {
+                global_pool.config_delay = DEFAULT_CONFIG_DELAY;
+            }
+            if global_pool.lock_tiers.iter().all(|tier| tier.multiplier_bps == 0) {
+                for (tier, (duration_days, multiplier_bps)) in
+                    global_pool.lock_tiers.iter_mut().zip(DEFAULT_LOCK_TIERS)
+                {
+                    tier.duration_days = duration_days;
+                    tier.multiplier_bps = multiplier_bps;
+                }
+            }
+            if global_pool.points_updated_at == 0 {
+                global_pool.points_updated_at = Clock::get()?.unix_timestamp;
+            }
+        }
+        global_pool.version = GLOBAL_POOL_VERSION;
+        global_pool.exit(&crate::ID)?;
+
+        Ok(())
+    }
+}
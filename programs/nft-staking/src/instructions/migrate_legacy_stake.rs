@@ -0,0 +1,122 @@
+use {
+    crate::*,
+    anchor_spl::token::{Mint, TokenAccount},
+    mpl_token_metadata::{
+        pda::find_token_record_account,
+        state::{TokenDelegateRole, TokenMetadataAccount, TokenRecord},
+    },
+};
+
+#[derive(Accounts)]
+pub struct MigrateLegacyStake<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    /// CHECK owner of the legacy staked pNFT, checked by token account
+    pub owner: UncheckedAccount<'info>,
+
+    //  User pool must be migrated first
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    #[account(
+        token::mint = token_mint,
+        token::authority = owner,
+        constraint = token_account.amount == 1 @StakingError::NotLegacyStake,
+    )]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+    pub token_mint: Box<Account<'info, Mint>>,
+    /// CHECK token record of the token account, parsed in instruction
+    #[account(
+        address = find_token_record_account(&token_mint.key(), &token_account.key()).0,
+        owner = mpl_token_metadata::ID,
+    )]
+    pub token_mint_record: UncheckedAccount<'info>,
+
+    //  Stake info the legacy program never created
+    #[account(
+        init,
+        space = 8 + StakedNft::DATA_SIZE,
+        seeds = [STAKED_NFT_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+        payer = payer
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            owner.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = payer
+    )]
+    pub stake_checkpoint: Box<Account<'info, StakeCheckpoint>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateLegacyStake<'_> {
+    /**
+     * pNFT locked by the legacy program has user pool as staking delegate and no stake info
+     * collection was checked when it was locked, so only rarity weight is proved here
+     */
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        weight: u16,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let token_record = TokenRecord::from_account_info(&ctx.accounts.token_mint_record)?;
+        require!(
+            token_record.is_locked()
+                && token_record.delegate == Some(ctx.accounts.user_pool.key())
+                && token_record.delegate_role == Some(TokenDelegateRole::Staking),
+            StakingError::NotLegacyStake
+        );
+
+        let rarity_weight = ctx.accounts.global_pool.rarity_weight(
+            &ctx.accounts.token_mint.key(),
+            weight,
+            &proof,
+        )?;
+
+        // Legacy stakes had no lock commitment
+        let lock_tier = LockTier {
+            duration_days: 0,
+            multiplier_bps: BASE_MULTIPLIER,
+        };
+        let timestamp = Clock::get()?.unix_timestamp;
+        let user_pool = &mut ctx.accounts.user_pool;
+        let global_pool = &mut ctx.accounts.global_pool;
+        let staked_nft = &mut ctx.accounts.staked_nft;
+        staked_nft.stake(
+            ctx.accounts.owner.key(),
+            ctx.accounts.token_mint.key(),
+            lock_tier,
+            rarity_weight,
+            Custody::TokenMetadataLock,
+            timestamp,
+        );
+
+        user_pool.settle_rewards(global_pool, timestamp)?;
+        user_pool.checkpoint_stake(global_pool, &mut ctx.accounts.stake_checkpoint);
+        user_pool.add_stake(global_pool, staked_nft.weight, timestamp);
+
+        Ok(())
+    }
+}
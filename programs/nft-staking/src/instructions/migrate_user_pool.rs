@@ -0,0 +1,60 @@
+use {crate::*, anchor_lang::Discriminator};
+
+#[derive(Accounts)]
+pub struct MigrateUserPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    /// CHECK owner of the user pool
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK older layout doesn't deserialize, checked and grown in instruction
+    #[account(
+        mut,
+        seeds = [user.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateUserPool<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>) -> Result<()> {
+        let user_info = ctx.accounts.user_pool.to_account_info();
+        grow_account(
+            &user_info,
+            UserPool::DISCRIMINATOR,
+            8 + UserPool::DATA_SIZE,
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        let mut user_pool: Account<UserPool> = Account::try_from(&user_info)?;
+        if user_pool.version == 0 {
+            // Legacy stakes have no stake info or weight, migrate_legacy_stake counts them again
+            user_pool.stake_cnt = 0;
+            if user_pool.last_settled_at == 0 {
+                user_pool.last_settled_at = Clock::get()?.unix_timestamp;
+            }
+            // Zero index would pay points accrued before the user pool was migrated
+            if user_pool.points_index == 0 {
+                user_pool.points_index = ctx.accounts.global_pool.points_per_nft;
+            }
+            // Same for revenue deposited before it
+            if user_pool.revenue_index == 0 {
+                user_pool.revenue_index = ctx.accounts.global_pool.revenue_per_nft;
+            }
+        }
+        user_pool.version = USER_POOL_VERSION;
+        user_pool.exit(&crate::ID)?;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,90 @@
 pub mod initialize;
 pub use initialize::*;
-pub mod change_admin;
-pub use change_admin::*;
+pub mod queue_config_change;
+pub use queue_config_change::*;
+pub mod execute_config_change;
+pub use execute_config_change::*;
+pub mod cancel_config_change;
+pub use cancel_config_change::*;
+pub mod add_blocklist;
+pub use add_blocklist::*;
+pub mod remove_blocklist;
+pub use remove_blocklist::*;
+pub mod withdraw_treasury;
+pub use withdraw_treasury::*;
+pub mod add_reward_stream;
+pub use add_reward_stream::*;
+pub mod fund_rewards;
+pub use fund_rewards::*;
+pub mod adjust_points;
+pub use adjust_points::*;
+pub mod create_store_item;
+pub use create_store_item::*;
+pub mod create_poll;
+pub use create_poll::*;
+pub mod create_distribution;
+pub use create_distribution::*;
 pub mod init_user;
 pub use init_user::*;
 pub mod lock_pnft;
 pub use lock_pnft::*;
 pub mod unlock_pnft;
 pub use unlock_pnft::*;
+pub mod request_unlock;
+pub use request_unlock::*;
+pub mod complete_unlock;
+pub use complete_unlock::*;
+pub mod cancel_unlock;
+pub use cancel_unlock::*;
+pub mod force_unlock_pnft;
+pub use force_unlock_pnft::*;
+pub mod claim_reward;
+pub use claim_reward::*;
+pub mod spend_points;
+pub use spend_points::*;
+pub mod redeem_item;
+pub use redeem_item::*;
+pub mod set_reward_recipient;
+pub use set_reward_recipient::*;
+pub mod crank_claim;
+pub use crank_claim::*;
+pub mod withdraw_vested;
+pub use withdraw_vested::*;
+pub mod deposit_revenue;
+pub use deposit_revenue::*;
+pub mod claim_revenue;
+pub use claim_revenue::*;
+pub mod update_voter_weight_record;
+pub use update_voter_weight_record::*;
+pub mod cast_vote;
+pub use cast_vote::*;
+pub mod snapshot;
+pub use snapshot::*;
+pub mod claim_airdrop;
+pub use claim_airdrop::*;
+pub mod lock_core;
+pub use lock_core::*;
+pub mod unlock_core;
+pub use unlock_core::*;
+pub mod force_unlock_core;
+pub use force_unlock_core::*;
+pub mod lock_cnft;
+pub use lock_cnft::*;
+pub mod unlock_cnft;
+pub use unlock_cnft::*;
+pub mod force_unlock_cnft;
+pub use force_unlock_cnft::*;
+pub mod stake_tokens;
+pub use stake_tokens::*;
+pub mod unstake_tokens;
+pub use unstake_tokens::*;
+pub mod harvest_receipt;
+pub use harvest_receipt::*;
+pub mod sell_pnft;
+pub use sell_pnft::*;
+pub mod migrate_global_pool;
+pub use migrate_global_pool::*;
+pub mod migrate_user_pool;
+pub use migrate_user_pool::*;
+pub mod migrate_legacy_stake;
+pub use migrate_legacy_stake::*;
@@ -0,0 +1,40 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    // Current admin
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    //  Global pool stores admin address
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
+    //  Pending change stores the queued change until its eta
+    #[account(
+        init,
+        space = 8 + PendingChange::DATA_SIZE,
+        seeds = [CONFIG_CHANGE_SEED.as_ref(), global_pool.change_cnt.to_le_bytes().as_ref()],
+        bump,
+        payer = admin
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl QueueConfigChange<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, change: ConfigChange) -> Result<()> {
+        let timestamp = Clock::get()?.unix_timestamp;
+        ctx.accounts.pending_change.queue(&mut ctx.accounts.global_pool, change, timestamp)
+    }
+}
@@ -0,0 +1,102 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct RedeemItem<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's points
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    #[account(
+        mut,
+        seeds = [STORE_ITEM_SEED.as_ref(), store_item.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub store_item: Box<Account<'info, StoreItem>>,
+
+    //  Receipt of user's redemptions of this item
+    #[account(
+        init_if_needed,
+        space = 8 + Redemption::DATA_SIZE,
+        seeds = [REDEMPTION_SEED.as_ref(), store_item.key().as_ref(), signer.key().as_ref()],
+        bump,
+        payer = signer
+    )]
+    pub redemption: Box<Account<'info, Redemption>>,
+
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn redeem_item_handler(ctx: Context<RedeemItem>, quantity: u32) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let store_item = &mut ctx.accounts.store_item;
+    require!(
+        timestamp >= store_item.start_time && timestamp < store_item.end_time,
+        StakingError::SaleNotActive
+    );
+    let sold = store_item
+        .sold
+        .checked_add(quantity)
+        .ok_or(StakingError::MathOverflow)?;
+    require!(
+        quantity > 0 && sold <= store_item.supply,
+        StakingError::SoldOut
+    );
+
+    let redemption = &mut ctx.accounts.redemption;
+    let redeemed = redemption
+        .quantity
+        .checked_add(quantity)
+        .ok_or(StakingError::MathOverflow)?;
+    require!(
+        store_item.per_wallet_limit == 0 || redeemed <= store_item.per_wallet_limit,
+        StakingError::ExceedWalletLimit
+    );
+
+    let global_pool = &mut ctx.accounts.global_pool;
+    let user_pool = &mut ctx.accounts.user_pool;
+    user_pool.settle_rewards(global_pool, timestamp)?;
+
+    let price = store_item
+        .price
+        .checked_mul(quantity as u64)
+        .ok_or(StakingError::MathOverflow)?;
+    user_pool.points = user_pool
+        .points
+        .checked_sub(price)
+        .ok_or(StakingError::InsufficientPoints)?;
+
+    store_item.sold = sold;
+
+    redemption.item = store_item.key();
+    redemption.user = ctx.accounts.signer.key();
+    redemption.quantity = redeemed;
+    redemption.points_spent = redemption
+        .points_spent
+        .checked_add(price)
+        .ok_or(StakingError::MathOverflow)?;
+    redemption.last_redeemed_at = timestamp;
+
+    emit!(ItemRedeemed {
+        user: redemption.user,
+        item: redemption.item,
+        quantity,
+        points_spent: price,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct RemoveBlocklist<'info> {
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
+    //  Blocklist entry is closed to unblock the address
+    #[account(
+        mut,
+        seeds = [BLOCKLIST_SEED.as_ref(), blocklist_entry.address.as_ref()],
+        bump,
+        close = admin
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+}
+
+impl RemoveBlocklist<'_> {
+    pub fn process_instruction(_ctx: &mut Context<Self>) -> Result<()> {
+        // Closing the blocklist entry unblocks the address
+        Ok(())
+    }
+}
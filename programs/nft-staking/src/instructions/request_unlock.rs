@@ -0,0 +1,75 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct RequestUnlock<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Stake info of the NFT to unbond
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), staked_nft.mint.as_ref()],
+        bump,
+        constraint = staked_nft.owner == signer.key() @StakingError::NftNotExist,
+        constraint = staked_nft.custody != Custody::ReceiptEscrow @StakingError::InvalidCustody,
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            signer.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = signer
+    )]
+    pub stake_checkpoint: Box<Account<'info, StakeCheckpoint>>,
+
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn request_unlock_handler(ctx: Context<RequestUnlock>) -> Result<()> {
+    let staked_nft = &mut ctx.accounts.staked_nft;
+    require!(
+        !staked_nft.is_unbonding(),
+        StakingError::UnlockAlreadyRequested
+    );
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    require!(timestamp >= staked_nft.lock_end, StakingError::StillLocked);
+    require!(
+        timestamp >= ctx.accounts.user_pool.vote_lock_end,
+        StakingError::VoteActive
+    );
+
+    staked_nft.unlock_requested_at = timestamp;
+
+    // Unbonding NFT doesn't count as staked anymore
+    let user_pool = &mut ctx.accounts.user_pool;
+    let global_pool = &mut ctx.accounts.global_pool;
+    user_pool.settle_rewards(global_pool, timestamp)?;
+    user_pool.checkpoint_stake(global_pool, &mut ctx.accounts.stake_checkpoint);
+    user_pool.remove_stake(global_pool, staked_nft.weight)?;
+
+    Ok(())
+}
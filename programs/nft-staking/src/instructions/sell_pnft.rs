@@ -0,0 +1,189 @@
+use {
+    crate::*,
+    anchor_lang::solana_program::sysvar,
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token_interface::{Mint, TokenAccount, TokenInterface},
+    },
+};
+
+#[derive(Accounts)]
+pub struct SellPNFT<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = signer,
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK instruction will fail if wrong edition is supplied
+    pub token_mint_edition: AccountInfo<'info>,
+    /// CHECK instruction will fail if wrong record is supplied
+    #[account(mut)]
+    pub token_mint_record: AccountInfo<'info>,
+    /// CHECK instruction will fail if wrong metadata is supplied
+    #[account(mut)]
+    pub mint_metadata: UncheckedAccount<'info>,
+    /// CHECK instruction will fail if wrong rules are supplied
+    pub auth_rules: UncheckedAccount<'info>,
+    /// CHECK address is checked, used to find calling program
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: AccountInfo<'info>,
+    //  Seller, signature is forwarded by the marketplace
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores seller's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Stake info of this NFT, closed on sale
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+        constraint = staked_nft.owner == signer.key() @StakingError::NftNotExist,
+        constraint = staked_nft.custody == Custody::TokenMetadataLock @StakingError::SaleCustodyUnsupported,
+        close = signer
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            signer.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = signer
+    )]
+    pub stake_checkpoint: Box<Account<'info, StakeCheckpoint>>,
+
+    /// CHECK Buyer receiving the pNFT
+    pub buyer: UncheckedAccount<'info>,
+    /// CHECK Token account of buyer, created by token metadata if missing
+    #[account(mut)]
+    pub buyer_token_account: UncheckedAccount<'info>,
+    /// CHECK instruction will fail if wrong record is supplied
+    #[account(mut)]
+    pub buyer_token_record: UncheckedAccount<'info>,
+
+    //  Treasury collects unlock fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED.as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK intstruction will fail if wrong program is supplied
+    pub token_metadata_program: AccountInfo<'info>,
+    /// CHECK intstruction will fail if wrong program is supplied
+    pub auth_rules_program: AccountInfo<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SellPNFT<'info> {
+    pub fn pnft_accounts(&self) -> PnftAccounts<'info> {
+        PnftAccounts {
+            delegate: self.user_pool.to_account_info(),
+            token_owner: self.signer.to_account_info(),
+            token_account: self.token_account.to_account_info(),
+            token_mint: self.token_mint.to_account_info(),
+            token_mint_edition: self.token_mint_edition.to_account_info(),
+            token_mint_record: self.token_mint_record.to_account_info(),
+            mint_metadata: self.mint_metadata.to_account_info(),
+            payer: self.signer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            sysvar_instructions: self.sysvar_instructions.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            auth_rules_program: self.auth_rules_program.to_account_info(),
+            auth_rules: self.auth_rules.to_account_info(),
+        }
+    }
+}
+
+/**
+ * Allowlisted marketplace replaces admin co-sign of unlock_pnft
+ */
+pub fn sell_pnft_handler(ctx: Context<SellPNFT>) -> Result<()> {
+    let caller = direct_caller(&ctx.accounts.sysvar_instructions)?;
+    require!(
+        ctx.accounts.global_pool.is_marketplace(&caller),
+        StakingError::UnauthorizedCaller
+    );
+
+    // Same exit rules as unlock_pnft, with unbonding period set only unbonded NFTs can be sold
+    let timestamp = Clock::get()?.unix_timestamp;
+    ctx.accounts.staked_nft.check_unlock(
+        &ctx.accounts.global_pool,
+        ctx.accounts.user_pool.vote_lock_end,
+        timestamp,
+    )?;
+
+    let signer = ctx.accounts.signer.key();
+    let seeds = &[
+        signer.as_ref(),
+        USER_POOL_SEED.as_bytes(),
+        &[*ctx.bumps.get("user_pool").unwrap()],
+    ];
+    let delegate_seeds = &[&seeds[..]];
+
+    let pnft_accounts = ctx.accounts.pnft_accounts();
+    unlock_and_revoke_pnft(
+        &pnft_accounts,
+        &ctx.accounts.signer.to_account_info(),
+        delegate_seeds,
+    )?;
+
+    // Seller signature covers the transfer to buyer
+    transfer_pnft(
+        &pnft_accounts,
+        &ctx.accounts.buyer_token_account.to_account_info(),
+        &ctx.accounts.buyer.to_account_info(),
+        &ctx.accounts.buyer_token_record.to_account_info(),
+        &ctx.accounts.associated_token_program.to_account_info(),
+        &[],
+    )?;
+
+    transfer_fee(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.global_pool.unlock_fee,
+    )?;
+
+    // Rewards up to the sale stay with seller
+    if !ctx.accounts.staked_nft.is_unbonding() {
+        let user_pool = &mut ctx.accounts.user_pool;
+        let global_pool = &mut ctx.accounts.global_pool;
+        user_pool.settle_rewards(global_pool, timestamp)?;
+        user_pool.checkpoint_stake(global_pool, &mut ctx.accounts.stake_checkpoint);
+        user_pool.remove_stake(global_pool, ctx.accounts.staked_nft.weight)?;
+    }
+
+    emit!(StakeSold {
+        user: signer,
+        mint: ctx.accounts.token_mint.key(),
+        buyer: ctx.accounts.buyer.key(),
+        program: caller,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct SetRewardRecipient<'info> {
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+}
+
+pub fn set_reward_recipient_handler(
+    ctx: Context<SetRewardRecipient>,
+    recipient: Option<Pubkey>,
+) -> Result<()> {
+    let user_pool = &mut ctx.accounts.user_pool;
+    user_pool.reward_recipient = recipient;
+
+    Ok(())
+}
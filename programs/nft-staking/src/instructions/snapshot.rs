@@ -0,0 +1,48 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct TakeSnapshot<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        space = 8 + Snapshot::DATA_SIZE,
+        seeds = [SNAPSHOT_SEED.as_ref(), (global_pool.epoch + 1).to_le_bytes().as_ref()],
+        bump,
+        payer = payer
+    )]
+    pub snapshot: Box<Account<'info, Snapshot>>,
+
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn snapshot_handler(ctx: Context<TakeSnapshot>) -> Result<()> {
+    let clock = Clock::get()?;
+    let global_pool = &mut ctx.accounts.global_pool;
+    require!(
+        clock.unix_timestamp >= global_pool.last_snapshot_at + MIN_SNAPSHOT_INTERVAL,
+        StakingError::SnapshotTooEarly
+    );
+
+    global_pool.epoch += 1;
+    global_pool.last_snapshot_at = clock.unix_timestamp;
+
+    let snapshot = &mut ctx.accounts.snapshot;
+    snapshot.epoch = global_pool.epoch;
+    snapshot.taken_at = clock.unix_timestamp;
+    snapshot.slot = clock.slot;
+    snapshot.total_stake_cnt = global_pool.total_stake_cnt;
+    // Receipt positions have no stake checkpoints, so their weight is left out
+    snapshot.total_stake_weight = global_pool.total_stake_weight - global_pool.receipt_weight;
+
+    Ok(())
+}
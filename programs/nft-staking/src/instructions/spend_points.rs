@@ -0,0 +1,51 @@
+use {crate::*, anchor_lang::solana_program::sysvar};
+
+#[derive(Accounts)]
+pub struct SpendPoints<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's points
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    /// CHECK address is checked, used to find calling program
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: AccountInfo<'info>,
+}
+
+pub fn spend_points_handler(ctx: Context<SpendPoints>, amount: u64) -> Result<()> {
+    let caller = direct_caller(&ctx.accounts.sysvar_instructions)?;
+    let global_pool = &mut ctx.accounts.global_pool;
+    require!(
+        global_pool.is_points_spender(&caller),
+        StakingError::UnauthorizedCaller
+    );
+
+    let user_pool = &mut ctx.accounts.user_pool;
+    user_pool.settle_rewards(global_pool, Clock::get()?.unix_timestamp)?;
+
+    user_pool.points = user_pool
+        .points
+        .checked_sub(amount)
+        .ok_or(StakingError::InsufficientPoints)?;
+
+    emit!(PointsSpent {
+        user: user_pool.user,
+        program: caller,
+        amount,
+        balance: user_pool.points,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use {
+    crate::*,
+    anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+#[derive(Accounts)]
+pub struct StakeTokens<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    #[account(
+        address = global_pool.stake_mint @StakingError::TokenStakingDisabled
+    )]
+    pub stake_mint: Box<Account<'info, Mint>>,
+
+    //  Vault holds tokens staked by all users, one per stake mint
+    #[account(
+        init_if_needed,
+        seeds = [TOKEN_VAULT_SEED.as_ref(), stake_mint.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = global_pool,
+        payer = signer
+    )]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = stake_mint,
+        token::authority = signer,
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK blocklist entry of signer, must not exist
+    #[account(
+        seeds = [BLOCKLIST_SEED.as_ref(), signer.key().as_ref()],
+        bump,
+    )]
+    pub wallet_blocklist: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn stake_tokens_handler(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.global_pool.token_weight_ratio > 0,
+        StakingError::TokenStakingDisabled
+    );
+    require!(amount > 0, StakingError::InvalidAmount);
+    require!(
+        ctx.accounts.wallet_blocklist.data_is_empty(),
+        StakingError::WalletBlocked
+    );
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                to: ctx.accounts.token_vault.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    let user_pool = &mut ctx.accounts.user_pool;
+    let global_pool = &mut ctx.accounts.global_pool;
+    user_pool.settle_rewards(global_pool, timestamp)?;
+    let staked_amount = user_pool
+        .staked_amount
+        .checked_add(amount)
+        .ok_or(StakingError::MathOverflow)?;
+    user_pool.set_token_stake(global_pool, staked_amount)?;
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use {crate::*, std::str::FromStr};
+
+#[derive(Accounts)]
+#[instruction(asset_id: Pubkey)]
+pub struct UnlockCnft<'info> {
+    // Need admin sign to unlock compressed NFT
+    #[account(
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info, owns the leaf while staked
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    /// CHECK Tree config of the merkle tree, checked by bubblegum
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK Merkle tree that holds the leaf, checked by bubblegum
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    //  Stake info of this leaf, closed on unlock
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), asset_id.as_ref()],
+        bump,
+        constraint = staked_nft.owner == signer.key() @StakingError::NftNotExist,
+        constraint = staked_nft.custody == Custody::CompressedEscrow @StakingError::InvalidCustody,
+        close = signer
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            signer.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = signer
+    )]
+    pub stake_checkpoint: Box<Account<'info, StakeCheckpoint>>,
+
+    //  Treasury collects unlock fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED.as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK SPL noop program
+    #[account(address = Pubkey::from_str(SPL_NOOP_PROGRAM_ADDRESS).unwrap())]
+    pub log_wrapper: UncheckedAccount<'info>,
+    /// CHECK SPL account compression program
+    #[account(address = Pubkey::from_str(SPL_COMPRESSION_PROGRAM_ADDRESS).unwrap())]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK Bubblegum program
+    #[account(address = Pubkey::from_str(BUBBLEGUM_PROGRAM_ADDRESS).unwrap())]
+    pub bubblegum_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UnlockCnft<'info> {
+    pub fn cnft_accounts(&self) -> CnftAccounts<'info> {
+        CnftAccounts {
+            tree_authority: self.tree_authority.to_account_info(),
+            merkle_tree: self.merkle_tree.to_account_info(),
+            log_wrapper: self.log_wrapper.to_account_info(),
+            compression_program: self.compression_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            bubblegum_program: self.bubblegum_program.to_account_info(),
+        }
+    }
+}
+
+/**
+ * Proof nodes of the leaf are passed through remaining accounts
+ */
+pub fn unlock_cnft_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, UnlockCnft<'info>>,
+    asset_id: Pubkey,
+    root: [u8; 32],
+    nonce: u64,
+    index: u32,
+    metadata: MetadataArgs,
+) -> Result<()> {
+    require!(
+        asset_id == get_asset_id(&ctx.accounts.merkle_tree.key(), nonce),
+        StakingError::InvalidMetadata
+    );
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    ctx.accounts.staked_nft.check_unlock(
+        &ctx.accounts.global_pool,
+        ctx.accounts.user_pool.vote_lock_end,
+        timestamp,
+    )?;
+
+    let signer = ctx.accounts.signer.key();
+    let seeds = &[
+        signer.as_ref(),
+        USER_POOL_SEED.as_bytes(),
+        &[*ctx.bumps.get("user_pool").unwrap()],
+    ];
+    let owner_seeds = &[&seeds[..]];
+
+    // Return leaf to user, user pool signs as leaf owner
+    let user_pool = ctx.accounts.user_pool.to_account_info();
+    transfer_cnft(
+        &ctx.accounts.cnft_accounts(),
+        &user_pool,
+        &user_pool,
+        &ctx.accounts.signer.to_account_info(),
+        ctx.remaining_accounts,
+        root,
+        &metadata,
+        nonce,
+        index,
+        owner_seeds,
+    )?;
+
+    transfer_fee(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.global_pool.unlock_fee,
+    )?;
+
+    // Unbonding leaf is already excluded from stake count
+    if !ctx.accounts.staked_nft.is_unbonding() {
+        let user_pool = &mut ctx.accounts.user_pool;
+        let global_pool = &mut ctx.accounts.global_pool;
+        user_pool.settle_rewards(global_pool, timestamp)?;
+        user_pool.checkpoint_stake(global_pool, &mut ctx.accounts.stake_checkpoint);
+        user_pool.remove_stake(global_pool, ctx.accounts.staked_nft.weight)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use {crate::*, std::str::FromStr};
+
+#[derive(Accounts)]
+pub struct UnlockCore<'info> {
+    // Need admin sign to unlock asset
+    #[account(
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    /// CHECK Core asset frozen by user pool
+    #[account(
+        mut,
+        owner = Pubkey::from_str(MPL_CORE_PROGRAM_ADDRESS).unwrap() @StakingError::InvalidMetadata,
+    )]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK Core collection of the asset
+    #[account(
+        mut,
+        address = Pubkey::from_str(CORE_COLLECTION).unwrap() @StakingError::InvalidCollection,
+    )]
+    pub collection: UncheckedAccount<'info>,
+
+    //  Stake info of this asset, closed on unlock
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), asset.key().as_ref()],
+        bump,
+        constraint = staked_nft.owner == signer.key() @StakingError::NftNotExist,
+        constraint = staked_nft.custody == Custody::CoreFreeze @StakingError::InvalidCustody,
+        close = signer
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            signer.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = signer
+    )]
+    pub stake_checkpoint: Box<Account<'info, StakeCheckpoint>>,
+
+    //  Treasury collects unlock fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED.as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK Metaplex Core program
+    #[account(address = Pubkey::from_str(MPL_CORE_PROGRAM_ADDRESS).unwrap())]
+    pub core_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UnlockCore<'info> {
+    pub fn core_accounts(&self) -> CoreAccounts<'info> {
+        CoreAccounts {
+            asset: self.asset.to_account_info(),
+            collection: self.collection.to_account_info(),
+            payer: self.signer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            core_program: self.core_program.to_account_info(),
+        }
+    }
+}
+
+pub fn unlock_core_handler(ctx: Context<UnlockCore>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    ctx.accounts.staked_nft.check_unlock(
+        &ctx.accounts.global_pool,
+        ctx.accounts.user_pool.vote_lock_end,
+        timestamp,
+    )?;
+
+    let signer = ctx.accounts.signer.key();
+    let seeds = &[
+        signer.as_ref(),
+        USER_POOL_SEED.as_bytes(),
+        &[*ctx.bumps.get("user_pool").unwrap()],
+    ];
+    let delegate_seeds = &[&seeds[..]];
+
+    thaw_core_asset(
+        &ctx.accounts.core_accounts(),
+        &ctx.accounts.user_pool.to_account_info(),
+        delegate_seeds,
+    )?;
+
+    transfer_fee(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.global_pool.unlock_fee,
+    )?;
+
+    // Unbonding asset is already excluded from stake count
+    if !ctx.accounts.staked_nft.is_unbonding() {
+        let user_pool = &mut ctx.accounts.user_pool;
+        let global_pool = &mut ctx.accounts.global_pool;
+        user_pool.settle_rewards(global_pool, timestamp)?;
+        user_pool.checkpoint_stake(global_pool, &mut ctx.accounts.stake_checkpoint);
+        user_pool.remove_stake(global_pool, ctx.accounts.staked_nft.weight)?;
+    }
+
+    Ok(())
+}
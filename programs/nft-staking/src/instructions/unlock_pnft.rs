@@ -1,13 +1,9 @@
 use {
     crate::*,
-    anchor_lang::{
-        solana_program::program::invoke_signed,
-        solana_program::program::invoke, 
-        solana_program::instruction::Instruction
+    anchor_spl::{
+        associated_token::{get_associated_token_address, AssociatedToken},
+        token_interface::{self, Burn, Mint, TokenAccount, TokenInterface},
     },
-    mpl_token_metadata::instruction::{MetadataInstruction, RevokeArgs, UnlockArgs},
-    anchor_spl::token::{Mint, Token, TokenAccount},
-//    anchor_spl::associated_token::{AssociatedToken}
 };
 
 #[derive(Accounts)]
@@ -19,18 +15,19 @@ pub struct UnlockPNFT<'info> {
     pub admin: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
         bump,
     )]
-    pub global_pool: Account<'info, GlobalPool>,
+    pub global_pool: Box<Account<'info, GlobalPool>>,
 
     #[account(
         mut, 
         token::mint = token_mint, 
         token::authority = signer,
     )]
-    pub token_account: Box<Account<'info, TokenAccount>>,
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
     /// CHECK instruction will fail if wrong edition is supplied
     pub token_mint_edition: AccountInfo<'info>,
     /// CHECK instruction will fail if wrong record is supplied
@@ -54,18 +51,106 @@ pub struct UnlockPNFT<'info> {
     )]
     pub user_pool: Box<Account<'info, UserPool>>,
 
-    pub token_program: Program<'info, Token>,
+    //  Stake info of this NFT, closed on unlock
+    #[account(
+        mut,
+        seeds = [STAKED_NFT_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+        constraint = staked_nft.owner == signer.key()
+            || staked_nft.custody == Custody::ReceiptEscrow @StakingError::NftNotExist,
+        close = signer
+    )]
+    pub staked_nft: Box<Account<'info, StakedNft>>,
+
+    //  Stake count of user before this change, kept for snapshots taken since last change
+    //  receipt positions aren't in stake count and leave it out
+    #[account(
+        init_if_needed,
+        space = 8 + StakeCheckpoint::DATA_SIZE,
+        seeds = [
+            STAKE_CHECKPOINT_SEED.as_ref(),
+            signer.key().as_ref(),
+            global_pool.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = signer
+    )]
+    pub stake_checkpoint: Option<Box<Account<'info, StakeCheckpoint>>>,
+
+    //  Escrow holding Token-2022 NFT that token metadata can't lock
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED.as_ref(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub escrow_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    //  Receipt token of the position, burned by holder on unlock
+    #[account(
+        mut,
+        address = staked_nft.receipt_mint @StakingError::ReceiptRequired,
+    )]
+    pub receipt_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut)]
+    pub receipt_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// CHECK Token account of global pool holding pNFT of receipt position
+    #[account(
+        mut,
+        address = get_associated_token_address(&global_pool.key(), &token_mint.key()),
+    )]
+    pub receipt_escrow: Option<UncheckedAccount<'info>>,
+    /// CHECK instruction will fail if wrong record is supplied
+    #[account(mut)]
+    pub receipt_escrow_record: Option<UncheckedAccount<'info>>,
+
+    //  Treasury collects unlock fee
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED.as_ref()],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     /// CHECK intstruction will fail if wrong program is supplied
     pub token_metadata_program: AccountInfo<'info>,
     /// CHECK intstruction will fail if wrong program is supplied
     pub auth_rules_program: AccountInfo<'info>,
     pub system_program: Program<'info, System>,
-//    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+}
+
+impl<'info> UnlockPNFT<'info> {
+    pub fn pnft_accounts(&self) -> PnftAccounts<'info> {
+        PnftAccounts {
+            delegate: self.user_pool.to_account_info(),
+            token_owner: self.signer.to_account_info(),
+            token_account: self.token_account.to_account_info(),
+            token_mint: self.token_mint.to_account_info(),
+            token_mint_edition: self.token_mint_edition.to_account_info(),
+            token_mint_record: self.token_mint_record.to_account_info(),
+            mint_metadata: self.mint_metadata.to_account_info(),
+            payer: self.signer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            sysvar_instructions: self.sysvar_instructions.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            auth_rules_program: self.auth_rules_program.to_account_info(),
+            auth_rules: self.auth_rules.to_account_info(),
+        }
+    }
 }
 
 pub fn unlock_pnft_handler(ctx: Context<UnlockPNFT>) -> Result<()> {
-    
-    let user_pool = &mut ctx.accounts.user_pool;
+    if ctx.accounts.staked_nft.custody == Custody::ReceiptEscrow {
+        return unlock_receipt_position(ctx);
+    }
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    ctx.accounts.staked_nft.check_unlock(
+        &ctx.accounts.global_pool,
+        ctx.accounts.user_pool.vote_lock_end,
+        timestamp,
+    )?;
 
     let signer = ctx.accounts.signer.key();
 
@@ -76,108 +161,129 @@ pub fn unlock_pnft_handler(ctx: Context<UnlockPNFT>) -> Result<()> {
     ];
     let delegate_seeds = &[&seeds[..]];
     
-    invoke_signed(
-        &Instruction {
-            program_id: mpl_token_metadata::id(),
-            accounts: vec![
-                // 0. `[signer]` Delegate
-                AccountMeta::new_readonly(user_pool.key(), true),
-                // 1. `[optional]` Token owner
-                AccountMeta::new_readonly(ctx.accounts.signer.key(), false),
-                // 2. `[mut]` Token account
-                AccountMeta::new(ctx.accounts.token_account.key(), false),
-                // 3. `[]` Mint account
-                AccountMeta::new_readonly(ctx.accounts.token_mint.key(), false),
-                // 4. `[mut]` Metadata account
-                AccountMeta::new(ctx.accounts.mint_metadata.key(), false),
-                // 5. `[optional]` Edition account
-                AccountMeta::new_readonly(ctx.accounts.token_mint_edition.key(), false),
-                // 6. `[optional, mut]` Token record account
-                AccountMeta::new(ctx.accounts.token_mint_record.key(), false),
-                // 7. `[signer, mut]` Payer
-                AccountMeta::new(ctx.accounts.signer.key(), true),
-                // 8. `[]` System Program
-                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
-                // 9. `[]` Instructions sysvar account
-                AccountMeta::new_readonly(ctx.accounts.sysvar_instructions.key(), false),
-                // 10. `[optional]` SPL Token Program
-                AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
-                // 11. `[optional]` Token Authorization Rules program
-                AccountMeta::new_readonly(ctx.accounts.auth_rules_program.key(), false),
-                // 12. `[optional]` Token Authorization Rules account
-                AccountMeta::new_readonly(ctx.accounts.auth_rules.key(), false),
-            ],
-            data: MetadataInstruction::Unlock(UnlockArgs::V1 { authorization_data: None }).try_to_vec().unwrap(),
-        },
-        &[
-            user_pool.to_account_info(),
-            ctx.accounts.signer.to_account_info(),
-            ctx.accounts.token_account.to_account_info(),
-            ctx.accounts.token_mint.to_account_info(),
-            ctx.accounts.mint_metadata.to_account_info(),
-            ctx.accounts.token_mint_edition.to_account_info(),
-            ctx.accounts.token_mint_record.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-            ctx.accounts.sysvar_instructions.to_account_info(),
-            ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.auth_rules_program.to_account_info(),
-            ctx.accounts.auth_rules.to_account_info(),
-        ],
+    release_nft(
+        ctx.accounts.staked_nft.custody,
+        &ctx.accounts.pnft_accounts(),
+        &ctx.accounts.signer.to_account_info(),
+        ctx.accounts
+            .escrow_token_account
+            .as_ref()
+            .map(|escrow| escrow.to_account_info()),
         delegate_seeds,
     )?;
 
-    invoke(
-        &Instruction {
-            program_id: mpl_token_metadata::id(),
-            accounts: vec![
-                // #[account(0, optional, writable, name="delegate_record", desc="Delegate record account")]
-                AccountMeta::new_readonly(mpl_token_metadata::id(), false),
-                // #[account(1, name="delegate", desc="Owner of the delegated account")]
-                AccountMeta::new_readonly(user_pool.key(), false),
-                // #[account(2, writable, name = "metadata", desc = "Metadata account")]
-                AccountMeta::new(ctx.accounts.mint_metadata.key(), false),
-                // #[account(3, optional, name = "master_edition", desc = "Master Edition account")]
-                AccountMeta::new_readonly(ctx.accounts.token_mint_edition.key(), false),
-                // #[account(4, optional, writable, name = "token_record", desc = "Token record account")]
-                AccountMeta::new(ctx.accounts.token_mint_record.key(), false),
-                // #[account(5, name = "mint", desc = "Mint of metadata")]
-                AccountMeta::new_readonly(ctx.accounts.token_mint.key(), false),
-                // #[account(6, optional, writable, name = "token", desc = "Token account of mint")]
-                AccountMeta::new(ctx.accounts.token_account.key(), false),
-                // #[account(7, signer, name = "authority", desc = "Update authority or token owner")]
-                AccountMeta::new_readonly(ctx.accounts.signer.key(), true),
-                // #[account(8, signer, writable, name = "payer", desc = "Payer")]
-                AccountMeta::new(ctx.accounts.signer.key(), true),
-                // #[account(9, name = "system_program", desc = "System Program")]
-                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
-                // #[account(10, name = "sysvar_instructions", desc = "Instructions sysvar account")]
-                AccountMeta::new_readonly(ctx.accounts.sysvar_instructions.key(), false),
-                // #[account(11, optional, name = "spl_token_program", desc = "SPL Token Program")]
-                AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
-                // #[account(12, optional, name = "authorization_rules_program", desc = "Token Authorization Rules Program")]
-                AccountMeta::new_readonly(ctx.accounts.auth_rules_program.key(), false),
-                // #[account(13, optional, name = "authorization_rules", desc = "Token Authorization Rules account")]
-                AccountMeta::new_readonly(ctx.accounts.auth_rules.key(), false),
-            ],
-            data: MetadataInstruction::Revoke(RevokeArgs::StakingV1).try_to_vec().unwrap(),
-        },
-        &[
-            user_pool.to_account_info(),
-            ctx.accounts.mint_metadata.to_account_info(),
-            ctx.accounts.token_mint_edition.to_account_info(),
-            ctx.accounts.token_mint_record.to_account_info(),
-            ctx.accounts.token_mint.to_account_info(),
-            ctx.accounts.token_account.to_account_info(),
-            ctx.accounts.signer.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-            ctx.accounts.sysvar_instructions.to_account_info(),
+    transfer_fee(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.global_pool.unlock_fee,
+    )?;
+
+    // Unbonding NFT is already excluded from stake count
+    if !ctx.accounts.staked_nft.is_unbonding() {
+        let user_pool = &mut ctx.accounts.user_pool;
+        let global_pool = &mut ctx.accounts.global_pool;
+        user_pool.settle_rewards(global_pool, timestamp)?;
+        user_pool.checkpoint_stake(
+            global_pool,
+            ctx.accounts
+                .stake_checkpoint
+                .as_mut()
+                .ok_or(StakingError::CheckpointRequired)?,
+        );
+        user_pool.remove_stake(global_pool, ctx.accounts.staked_nft.weight)?;
+    }
+
+    Ok(())
+}
+
+/**
+ * Receipt holder burns the receipt and takes the pNFT with accrued rewards
+ * receipt positions aren't tracked in user pools, so they exit in one step once lock ends
+ */
+fn unlock_receipt_position(ctx: Context<UnlockPNFT>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    require!(
+        timestamp >= ctx.accounts.staked_nft.lock_end,
+        StakingError::StillLocked
+    );
+
+    let (
+        Some(receipt_mint),
+        Some(receipt_token_account),
+        Some(receipt_escrow),
+        Some(receipt_escrow_record),
+        Some(associated_token_program),
+    ) = (
+        ctx.accounts.receipt_mint.as_ref(),
+        ctx.accounts.receipt_token_account.as_ref(),
+        ctx.accounts.receipt_escrow.as_ref(),
+        ctx.accounts.receipt_escrow_record.as_ref(),
+        ctx.accounts.associated_token_program.as_ref(),
+    ) else {
+        return err!(StakingError::ReceiptRequired);
+    };
+    require!(
+        receipt_token_account.mint == receipt_mint.key()
+            && receipt_token_account.owner == ctx.accounts.signer.key()
+            && receipt_token_account.amount == 1,
+        StakingError::ReceiptRequired
+    );
+
+    token_interface::burn(
+        CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.auth_rules_program.to_account_info(),
-            ctx.accounts.auth_rules.to_account_info(),
-        ],
+            Burn {
+                mint: receipt_mint.to_account_info(),
+                from: receipt_token_account.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+
+    // Global pool owns the escrowed pNFT and signs the transfer to holder
+    let global_seeds = &[
+        GLOBAL_AUTHORITY_SEED.as_bytes(),
+        &[*ctx.bumps.get("global_pool").unwrap()],
+    ];
+    let escrow_accounts = PnftAccounts {
+        delegate: ctx.accounts.global_pool.to_account_info(),
+        token_owner: ctx.accounts.global_pool.to_account_info(),
+        token_account: receipt_escrow.to_account_info(),
+        token_mint: ctx.accounts.token_mint.to_account_info(),
+        token_mint_edition: ctx.accounts.token_mint_edition.to_account_info(),
+        token_mint_record: receipt_escrow_record.to_account_info(),
+        mint_metadata: ctx.accounts.mint_metadata.to_account_info(),
+        payer: ctx.accounts.signer.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions: ctx.accounts.sysvar_instructions.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        auth_rules_program: ctx.accounts.auth_rules_program.to_account_info(),
+        auth_rules: ctx.accounts.auth_rules.to_account_info(),
+    };
+    transfer_pnft(
+        &escrow_accounts,
+        &ctx.accounts.token_account.to_account_info(),
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.token_mint_record,
+        &associated_token_program.to_account_info(),
+        &[&global_seeds[..]],
+    )?;
+
+    transfer_fee(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.global_pool.unlock_fee,
     )?;
 
-    user_pool.stake_cnt -= 1;
+    // Pending rewards go to holder's user pool, claimed through claim_reward
+    ctx.accounts.staked_nft.close_position(
+        &mut ctx.accounts.global_pool,
+        &mut ctx.accounts.user_pool,
+        timestamp,
+    );
 
     Ok(())
 }
@@ -0,0 +1,69 @@
+use {
+    crate::*,
+    anchor_spl::token::{Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct UnstakeTokens<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Vault holds tokens staked by all users, one per stake mint
+    #[account(
+        mut,
+        seeds = [TOKEN_VAULT_SEED.as_ref(), global_pool.stake_mint.as_ref()],
+        bump,
+    )]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_vault.mint,
+        token::authority = signer,
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn unstake_tokens_handler(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidAmount);
+    let user_pool = &mut ctx.accounts.user_pool;
+    require!(
+        amount <= user_pool.staked_amount,
+        StakingError::InsufficientStake
+    );
+
+    // Remaining tokens are reweighted at current ratio
+    let timestamp = Clock::get()?.unix_timestamp;
+    let global_pool = &mut ctx.accounts.global_pool;
+    user_pool.settle_rewards(global_pool, timestamp)?;
+    let staked_amount = user_pool.staked_amount - amount;
+    user_pool.set_token_stake(global_pool, staked_amount)?;
+
+    transfer_from_vault(
+        ctx.accounts.token_vault.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.global_pool.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        *ctx.bumps.get("global_pool").unwrap(),
+        amount,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(realm: Pubkey, governing_token_mint: Pubkey)]
+pub struct UpdateVoterWeightRecord<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    //  Voter signs since casting a vote locks their NFTs
+    pub owner: Signer<'info>,
+
+    //  PDA that stores stake info of voter
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Voter weight record of user in the realm
+    #[account(
+        init_if_needed,
+        space = 8 + VoterWeightRecord::DATA_SIZE,
+        seeds = [
+            VOTER_WEIGHT_RECORD_SEED.as_ref(),
+            realm.as_ref(),
+            governing_token_mint.as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = payer
+    )]
+    pub voter_weight_record: Box<Account<'info, VoterWeightRecord>>,
+
+    //  Needed to init new account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_voter_weight_record_handler(
+    ctx: Context<UpdateVoterWeightRecord>,
+    realm: Pubkey,
+    governing_token_mint: Pubkey,
+    action: VoterWeightAction,
+    target: Option<Pubkey>,
+) -> Result<()> {
+    let user_pool = &mut ctx.accounts.user_pool;
+    let record = &mut ctx.accounts.voter_weight_record;
+
+    // Voted NFTs can't be unstaked and voted again from another wallet on the same proposal
+    if action == VoterWeightAction::CastVote {
+        require!(target.is_some(), StakingError::ActionTargetRequired);
+        user_pool.vote_lock_end = user_pool
+            .vote_lock_end
+            .max(Clock::get()?.unix_timestamp + GOVERNANCE_VOTE_LOCK);
+    }
+
+    record.realm = realm;
+    record.governing_token_mint = governing_token_mint;
+    record.governing_token_owner = user_pool.user;
+    // Unbonding NFTs are not counted in stake count
+    record.voter_weight = user_pool.stake_cnt as u64 * VOTER_WEIGHT_PER_NFT;
+    record.voter_weight_expiry = Some(Clock::get()?.slot);
+    record.weight_action = Some(action);
+    record.weight_action_target = target;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use {
+    crate::*,
+    anchor_lang::system_program::{transfer, Transfer},
+};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(
+        mut,
+        constraint = global_pool.admin == *admin.key @StakingError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump
+    )]
+    pub global_pool: Account<'info, GlobalPool>,
+
+    //  Treasury holds collected fees
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED.as_ref()],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl WithdrawTreasury<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, amount: u64) -> Result<()> {
+        let treasury = &ctx.accounts.treasury;
+
+        let rent_exempt = Rent::get()?.minimum_balance(0);
+        let remaining = treasury
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(StakingError::InsufficientTreasury)?;
+        require!(remaining >= rent_exempt, StakingError::InsufficientTreasury);
+
+        let seeds = &[
+            TREASURY_SEED.as_bytes(),
+            &[*ctx.bumps.get("treasury").unwrap()],
+        ];
+        let signer = &[&seeds[..]];
+
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: treasury.to_account_info(),
+                    to: ctx.accounts.admin.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use {
+    crate::*,
+    anchor_spl::token::{Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+#[instruction(stream_index: u8)]
+pub struct WithdrawVested<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_ref()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    pub signer: Signer<'info>,
+
+    //  PDA that stores user's stake info
+    #[account(
+        seeds = [signer.key().as_ref(), USER_POOL_SEED.as_ref()],
+        bump,
+    )]
+    pub user_pool: Box<Account<'info, UserPool>>,
+
+    //  Vesting schedule of user's rewards of the stream
+    #[account(
+        mut,
+        seeds = [VESTING_SEED.as_ref(), signer.key().as_ref(), stream_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub vesting: Box<Account<'info, Vesting>>,
+
+    //  Vault of the reward stream
+    #[account(
+        mut,
+        seeds = [REWARD_VAULT_SEED.as_ref(), stream_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    //  Token account of user, or of reward recipient if set
+    #[account(
+        mut,
+        token::mint = reward_vault.mint,
+        constraint = user_token_account.owner == user_pool.reward_destination() @StakingError::InvalidRecipient,
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn withdraw_vested_handler(ctx: Context<WithdrawVested>, stream_index: u8) -> Result<()> {
+    let vesting = &mut ctx.accounts.vesting;
+    vesting.release(Clock::get()?.unix_timestamp);
+    require!(vesting.released > 0, StakingError::NoVestedReward);
+
+    // Pay what vault holds, remainder stays released
+    let amount = vesting.released.min(ctx.accounts.reward_vault.amount);
+    require!(amount > 0, StakingError::RewardVaultEmpty);
+    vesting.released -= amount;
+
+    let global_pool = &mut ctx.accounts.global_pool;
+    global_pool.reward_streams[stream_index as usize].claimed += amount;
+
+    transfer_from_vault(
+        ctx.accounts.reward_vault.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        global_pool.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        *ctx.bumps.get("global_pool").unwrap(),
+        amount,
+    )?;
+
+    Ok(())
+}
@@ -2,12 +2,16 @@ use anchor_lang::{prelude::*, AnchorDeserialize};
 
 pub mod constant;
 pub mod error;
+pub mod event;
 pub mod instructions;
 pub mod state;
+pub mod util;
 use constant::*;
 use error::*;
+use event::*;
 use instructions::*;
 use state::*;
+use util::*;
 
 declare_id!("PLSeoaC7uLWpkbFjUMvF2Er4RXQThdA6T7S9ZY6BqiT");
 
@@ -23,9 +27,126 @@ pub mod nft_staking {
         Initialize::process_instruction(&mut ctx)
     }
 
-    //  Admin can hand over admin role
-    pub fn change_admin(mut ctx: Context<ChangeAdmin>, new_admin: Pubkey) -> Result<()> {
-        ChangeAdmin::process_instruction(&mut ctx, new_admin)
+    /**
+     * Admin queues a config change
+     * it can be executed once the config delay has passed
+     * handing over admin role goes through here as well
+     */
+    pub fn queue_config_change(
+        mut ctx: Context<QueueConfigChange>,
+        change: ConfigChange,
+    ) -> Result<()> {
+        QueueConfigChange::process_instruction(&mut ctx, change)
+    }
+
+    //  Admin applies a queued config change after its delay
+    pub fn execute_config_change(mut ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        ExecuteConfigChange::process_instruction(&mut ctx)
+    }
+
+    //  Admin drops a queued config change
+    pub fn cancel_config_change(mut ctx: Context<CancelConfigChange>) -> Result<()> {
+        CancelConfigChange::process_instruction(&mut ctx)
+    }
+
+    //  Admin blocks a wallet or a NFT mint from staking
+    pub fn add_blocklist(mut ctx: Context<AddBlocklist>, address: Pubkey) -> Result<()> {
+        AddBlocklist::process_instruction(&mut ctx, address)
+    }
+
+    //  Admin removes a wallet or a NFT mint from blocklist
+    pub fn remove_blocklist(mut ctx: Context<RemoveBlocklist>) -> Result<()> {
+        RemoveBlocklist::process_instruction(&mut ctx)
+    }
+
+    //  Admin withdraws collected fees, treasury keeps rent exempt minimum
+    pub fn withdraw_treasury(mut ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        WithdrawTreasury::process_instruction(&mut ctx, amount)
+    }
+
+    /**
+     * Admin adds a reward stream with its own token vault
+     * rate is reward amount per second for one NFT at base multiplier
+     */
+    pub fn add_reward_stream(
+        mut ctx: Context<AddRewardStream>,
+        rate: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        AddRewardStream::process_instruction(&mut ctx, rate, start_time, end_time)
+    }
+
+    /**
+     * Admin deposits reward tokens to a stream
+     * end time can be extended to keep the campaign running
+     */
+    pub fn fund_rewards(
+        mut ctx: Context<FundRewards>,
+        stream_index: u8,
+        amount: u64,
+        end_time: i64,
+    ) -> Result<()> {
+        FundRewards::process_instruction(&mut ctx, stream_index, amount, end_time)
+    }
+
+    /**
+     * Admin grants (positive delta) or deducts (negative delta) user points
+     * reason code is logged for off-chain bookkeeping
+     */
+    pub fn adjust_points(mut ctx: Context<AdjustPoints>, delta: i64, reason_code: u16) -> Result<()> {
+        AdjustPoints::process_instruction(&mut ctx, delta, reason_code)
+    }
+
+    //  Admin lists an item users can redeem with points
+    pub fn create_store_item(
+        mut ctx: Context<CreateStoreItem>,
+        id: u32,
+        price: u64,
+        supply: u32,
+        per_wallet_limit: u32,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        CreateStoreItem::process_instruction(
+            &mut ctx,
+            id,
+            price,
+            supply,
+            per_wallet_limit,
+            start_time,
+            end_time,
+        )
+    }
+
+    //  Admin creates a staker poll
+    pub fn create_poll(
+        mut ctx: Context<CreatePoll>,
+        id: u32,
+        option_cnt: u8,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        CreatePoll::process_instruction(&mut ctx, id, option_cnt, start_time, end_time)
+    }
+
+    //  Admin publishes a merkle airdrop and funds its vault
+    pub fn create_distribution(
+        mut ctx: Context<CreateDistribution>,
+        id: u32,
+        root: [u8; 32],
+        leaf_cnt: u32,
+        amount: u64,
+    ) -> Result<()> {
+        CreateDistribution::process_instruction(&mut ctx, id, root, leaf_cnt, amount)
+    }
+
+    /**
+     * Admin grows global pool written by an older program version to current layout
+     * zeroed config gets initialize defaults
+     */
+    pub fn migrate_global_pool(mut ctx: Context<MigrateGlobalPool>) -> Result<()> {
+        MigrateGlobalPool::process_instruction(&mut ctx)
     }
 
     //  Initialize user pool
@@ -33,17 +154,301 @@ pub mod nft_staking {
         InitUser::process_instruction(&mut ctx)
     }
 
+    //  Anyone can grow a user pool written by an older program version to current layout
+    pub fn migrate_user_pool(mut ctx: Context<MigrateUserPool>) -> Result<()> {
+        MigrateUserPool::process_instruction(&mut ctx)
+    }
+
+    /**
+     * Anyone can create stake info of a pNFT locked by the legacy program
+     * it counts towards stake totals again and can be unlocked with unlock_pnft
+     */
+    pub fn migrate_legacy_stake(
+        mut ctx: Context<MigrateLegacyStake>,
+        weight: u16,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        MigrateLegacyStake::process_instruction(&mut ctx, weight, proof)
+    }
+
     /**
      * User can unstake pNFTs from specific collection
+     * tier is the lock commitment which gives reward multiplier
+     * weight is the rarity weight of NFT, proved against admin published root
+     * receipt mode mints a transferable receipt token that owns the position,
+     * its mint is a new keypair signing the transaction,
+     * receipt positions earn reward streams only, not points, revenue or voting power
      */
-    pub fn lock_pnft(ctx: Context<LockPNFT>) -> Result<()> {
-        lock_pnft::lock_pnft_handler(ctx)
+    pub fn lock_pnft(
+        ctx: Context<LockPNFT>,
+        tier: u8,
+        weight: u16,
+        proof: Vec<[u8; 32]>,
+        receipt: bool,
+    ) -> Result<()> {
+        lock_pnft::lock_pnft_handler(ctx, tier, weight, proof, receipt)
     }
 
     /**
-     * User can unlock pNFTs when they want
+     * User can unlock pNFTs once their lock tier ended, also completes requested unlock of pNFTs
+     * receipt positions are unlocked by the receipt holder, burning the receipt
      */
     pub fn unlock_pnft(ctx: Context<UnlockPNFT>) -> Result<()> {
         unlock_pnft::unlock_pnft_handler(ctx)
     }
+
+    /**
+     * User starts unbonding of a staked pNFT
+     * it stops earning from now on
+     */
+    pub fn request_unlock(ctx: Context<RequestUnlock>) -> Result<()> {
+        request_unlock::request_unlock_handler(ctx)
+    }
+
+    /**
+     * User unlocks pNFT once unbonding period passed
+     * same checks as unlock_pnft, it only fails when unlock wasn't requested
+     */
+    pub fn complete_unlock(ctx: Context<CompleteUnlock>) -> Result<()> {
+        complete_unlock::complete_unlock_handler(ctx)
+    }
+
+    /**
+     * User puts unbonding pNFT back into staking
+     */
+    pub fn cancel_unlock(ctx: Context<CancelUnlock>) -> Result<()> {
+        cancel_unlock::cancel_unlock_handler(ctx)
+    }
+
+    /**
+     * Admin can unlock blocked pNFTs that are already staked
+     * owner gets no rewards for them, receipt positions go to the receipt holder
+     */
+    pub fn force_unlock_pnft(ctx: Context<ForceUnlockPNFT>) -> Result<()> {
+        force_unlock_pnft::force_unlock_pnft_handler(ctx)
+    }
+
+    /**
+     * User claims accrued rewards of a reward stream
+     * rewards go into user's vesting schedule while vesting duration is set
+     */
+    pub fn claim_reward(ctx: Context<ClaimReward>, stream_index: u8) -> Result<()> {
+        claim_reward::claim_reward_handler(ctx, stream_index)
+    }
+
+    /**
+     * User withdraws rewards released from vesting schedule of a stream
+     */
+    pub fn withdraw_vested(ctx: Context<WithdrawVested>, stream_index: u8) -> Result<()> {
+        withdraw_vested::withdraw_vested_handler(ctx, stream_index)
+    }
+
+    /**
+     * Allowed programs spend user points through CPI
+     * allowed program must be the top level instruction calling directly, user must sign
+     */
+    pub fn spend_points(ctx: Context<SpendPoints>, amount: u64) -> Result<()> {
+        spend_points::spend_points_handler(ctx, amount)
+    }
+
+    /**
+     * User redeems store item with points
+     * redemption receipt is read by off-chain fulfillment
+     */
+    pub fn redeem_item(ctx: Context<RedeemItem>, quantity: u32) -> Result<()> {
+        redeem_item::redeem_item_handler(ctx, quantity)
+    }
+
+    /**
+     * User sets wallet that receives rewards, none sends them to user
+     */
+    pub fn set_reward_recipient(
+        ctx: Context<SetRewardRecipient>,
+        recipient: Option<Pubkey>,
+    ) -> Result<()> {
+        set_reward_recipient::set_reward_recipient_handler(ctx, recipient)
+    }
+
+    /**
+     * Anyone can push user's rewards to reward recipient
+     * caller gets a tip from the reward vault
+     */
+    pub fn crank_claim(ctx: Context<CrankClaim>, stream_index: u8) -> Result<()> {
+        crank_claim::crank_claim_handler(ctx, stream_index)
+    }
+
+    /**
+     * Anyone deposits SOL revenue shared by staked NFTs
+     */
+    pub fn deposit_revenue(ctx: Context<DepositRevenue>, amount: u64) -> Result<()> {
+        deposit_revenue::deposit_revenue_handler(ctx, amount)
+    }
+
+    /**
+     * User claims SOL revenue accrued by staked NFTs
+     */
+    pub fn claim_revenue(ctx: Context<ClaimRevenue>) -> Result<()> {
+        claim_revenue::claim_revenue_handler(ctx)
+    }
+
+    /**
+     * Write governance voter weight of user from staked NFTs
+     * record expires at current slot so it must be updated in the same transaction as the vote
+     * target is the proposal or governance the action is for, casting a vote needs it
+     * and locks staked NFTs of voter for governance vote lock
+     */
+    pub fn update_voter_weight_record(
+        ctx: Context<UpdateVoterWeightRecord>,
+        realm: Pubkey,
+        governing_token_mint: Pubkey,
+        action: VoterWeightAction,
+        target: Option<Pubkey>,
+    ) -> Result<()> {
+        update_voter_weight_record::update_voter_weight_record_handler(
+            ctx,
+            realm,
+            governing_token_mint,
+            action,
+            target,
+        )
+    }
+
+    /**
+     * Staker votes in a poll with weight of staked NFT count
+     * unstaking is blocked until the poll ends
+     */
+    pub fn cast_vote(ctx: Context<CastVote>, option: u8) -> Result<()> {
+        cast_vote::cast_vote_handler(ctx, option)
+    }
+
+    /**
+     * Anyone opens a new epoch and records total stake at its boundary
+     * user stake at the boundary is checkpointed on their next stake change
+     */
+    pub fn snapshot(ctx: Context<TakeSnapshot>) -> Result<()> {
+        snapshot::snapshot_handler(ctx)
+    }
+
+    /**
+     * User claims airdrop amount proved against distribution root
+     */
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        claim_airdrop::claim_airdrop_handler(ctx, index, amount, proof)
+    }
+
+    /**
+     * User locks Metaplex Core asset with freeze delegate plugin of user pool
+     */
+    pub fn lock_core(
+        ctx: Context<LockCore>,
+        tier: u8,
+        weight: u16,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        lock_core::lock_core_handler(ctx, tier, weight, proof)
+    }
+
+    /**
+     * User unlocks Metaplex Core asset, also completes requested unlock of assets
+     */
+    pub fn unlock_core(ctx: Context<UnlockCore>) -> Result<()> {
+        unlock_core::unlock_core_handler(ctx)
+    }
+
+    /**
+     * Admin can unlock blocked Core assets that are already staked
+     * owner gets no rewards for them
+     */
+    pub fn force_unlock_core(ctx: Context<ForceUnlockCore>) -> Result<()> {
+        force_unlock_core::force_unlock_core_handler(ctx)
+    }
+
+    /**
+     * User locks compressed NFT by transferring the leaf to user pool
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn lock_cnft<'info>(
+        ctx: Context<'_, '_, '_, 'info, LockCnft<'info>>,
+        asset_id: Pubkey,
+        root: [u8; 32],
+        nonce: u64,
+        index: u32,
+        metadata: MetadataArgs,
+        tier: u8,
+        weight: u16,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        lock_cnft::lock_cnft_handler(
+            ctx, asset_id, root, nonce, index, metadata, tier, weight, proof,
+        )
+    }
+
+    /**
+     * User unlocks compressed NFT, also completes requested unlock of compressed NFTs
+     */
+    pub fn unlock_cnft<'info>(
+        ctx: Context<'_, '_, '_, 'info, UnlockCnft<'info>>,
+        asset_id: Pubkey,
+        root: [u8; 32],
+        nonce: u64,
+        index: u32,
+        metadata: MetadataArgs,
+    ) -> Result<()> {
+        unlock_cnft::unlock_cnft_handler(ctx, asset_id, root, nonce, index, metadata)
+    }
+
+    /**
+     * Admin can unlock blocked compressed NFTs that are already staked
+     * owner gets no rewards for them
+     */
+    pub fn force_unlock_cnft<'info>(
+        ctx: Context<'_, '_, '_, 'info, ForceUnlockCnft<'info>>,
+        asset_id: Pubkey,
+        root: [u8; 32],
+        nonce: u64,
+        index: u32,
+        metadata: MetadataArgs,
+    ) -> Result<()> {
+        force_unlock_cnft::force_unlock_cnft_handler(ctx, asset_id, root, nonce, index, metadata)
+    }
+
+    /**
+     * User stakes fungible tokens, weighted against NFTs by token weight ratio
+     */
+    pub fn stake_tokens(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
+        stake_tokens::stake_tokens_handler(ctx, amount)
+    }
+
+    /**
+     * User unstakes fungible tokens
+     */
+    pub fn unstake_tokens(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
+        unstake_tokens::unstake_tokens_handler(ctx, amount)
+    }
+
+    /**
+     * Receipt holder moves rewards accrued by the position into own user pool
+     * they are paid out through claim_reward
+     */
+    pub fn harvest_receipt(ctx: Context<HarvestReceipt>) -> Result<()> {
+        harvest_receipt::harvest_receipt_handler(ctx)
+    }
+
+    /**
+     * Allowlisted marketplace unlocks staked pNFT and transfers it to buyer through CPI
+     * marketplace must be the top level instruction calling directly
+     * seller keeps rewards settled up to the sale
+     * exit rules of unlock_pnft apply, so while unbonding period is set the seller requests unlock
+     * and the sale fills once unbonding ends
+     * only pNFTs locked in place can be sold, Token-2022 escrow and receipt positions fail with
+     * SaleCustodyUnsupported, receipt positions are sold by trading the receipt token instead
+     */
+    pub fn sell_pnft(ctx: Context<SellPNFT>) -> Result<()> {
+        sell_pnft::sell_pnft_handler(ctx)
+    }
 }
@@ -1,16 +1,799 @@
-use crate::*;
+use {
+    crate::*,
+    anchor_lang::{solana_program::keccak, Discriminator},
+};
 
 /**
- * Global pool stores admin address
+ * Global pool stores admin address and program config
  */
 #[account]
 #[derive(Default)]
 pub struct GlobalPool {
-    pub admin: Pubkey, //  32
+    pub admin: Pubkey,      //  32
+    pub config_delay: i64,  //  8
+    pub change_cnt: u64,    //  8
+    pub lock_fee: u64,      //  8
+    pub unlock_fee: u64,    //  8
+    pub unbonding_period: i64,  //  8
+    pub lock_tiers: [LockTier; MAX_LOCK_TIERS], //  4 * 4
+    pub stream_cnt: u8,     //  1
+    pub reward_streams: [RewardStream; MAX_REWARD_STREAMS],    //  104 * 4
+    pub total_stake_weight: u64,    //  8
+    pub weight_root: [u8; 32],      //  32
+    pub points_rate: u64,           //  8
+    pub points_per_nft: u64,        //  8
+    pub points_updated_at: i64,     //  8
+    pub points_spenders: [Pubkey; MAX_POINTS_SPENDERS], //  32 * 4
+    pub streak_steps: [StreakStep; MAX_STREAK_STEPS],   //  4 * 4
+    pub crank_tip_bps: u16,         //  2
+    pub vesting_duration: i64,      //  8  zero pays claimed rewards instantly
+    pub total_stake_cnt: u64,       //  8
+    pub revenue_per_nft: u128,      //  16  scaled by REWARD_PRECISION
+    pub revenue_carry: u64,         //  8  lamports not distributed yet
+    pub epoch: u32,                 //  4  count of snapshots taken
+    pub last_snapshot_at: i64,      //  8
+    pub stake_mint: Pubkey,         //  32  fungible token staked alongside NFTs
+    pub token_weight_ratio: u64,    //  8  token amount weighing as one default NFT, zero disables token staking
+    pub total_staked_amount: u64,   //  8
+    pub marketplaces: [Pubkey; MAX_MARKETPLACES],   //  32 * 4
+    pub version: u8,                //  1  layout version, zero before migration
+    pub receipt_weight: u64,        //  8  part of total stake weight held by receipt positions
 }
 
 impl GlobalPool {
-    pub const DATA_SIZE: usize = 32;
+    pub const DATA_SIZE: usize = 32 + 8 + 8 + 8 + 8 + 8
+        + LockTier::DATA_SIZE * MAX_LOCK_TIERS
+        + 1 + RewardStream::DATA_SIZE * MAX_REWARD_STREAMS
+        + 8 + 32
+        + 8 + 8 + 8 + 32 * MAX_POINTS_SPENDERS
+        + StreakStep::DATA_SIZE * MAX_STREAK_STEPS
+        + 2 + 8
+        + 8 + 16 + 8
+        + 4 + 8
+        + 32 + 8 + 8
+        + 32 * MAX_MARKETPLACES
+        + 1 + 8;
+
+    //  Checkpoint accumulators of all streams, must run before total weight or rates change
+    pub fn update_rewards(&mut self, timestamp: i64) {
+        let total_stake_weight = self.total_stake_weight;
+        for stream in self.reward_streams[..self.stream_cnt as usize].iter_mut() {
+            stream.update(total_stake_weight, timestamp);
+        }
+    }
+
+    //  Checkpoint points earned per staked NFT, must run before points rate changes
+    pub fn update_points(&mut self, timestamp: i64) {
+        if timestamp > self.points_updated_at {
+            let earned = self
+                .points_rate
+                .saturating_mul((timestamp - self.points_updated_at) as u64);
+            self.points_per_nft = self.points_per_nft.saturating_add(earned);
+            self.points_updated_at = timestamp;
+        }
+    }
+
+    //  Distribute lamports to staked NFTs, kept for later while nothing is staked
+    pub fn distribute_revenue(&mut self, amount: u64) -> Result<()> {
+        let distributable = self
+            .revenue_carry
+            .checked_add(amount)
+            .ok_or(StakingError::MathOverflow)?;
+        if self.total_stake_cnt == 0 {
+            self.revenue_carry = distributable;
+            return Ok(());
+        }
+
+        let per_nft = distributable as u128 * REWARD_PRECISION / self.total_stake_cnt as u128;
+        self.revenue_per_nft += per_nft;
+        // Rounding dust carries over to next deposit
+        self.revenue_carry =
+            distributable - (per_nft * self.total_stake_cnt as u128 / REWARD_PRECISION) as u64;
+        Ok(())
+    }
+
+    //  Stake weight of token amount at current ratio
+    pub fn token_weight(&self, amount: u64) -> Result<u64> {
+        if self.token_weight_ratio == 0 {
+            return Ok(0);
+        }
+        u64::try_from(
+            amount as u128 * DEFAULT_NFT_WEIGHT as u128 * BASE_MULTIPLIER as u128
+                / self.token_weight_ratio as u128,
+        )
+        .map_err(|_| error!(StakingError::MathOverflow))
+    }
+
+    //  Enabled lock tier by index
+    pub fn lock_tier(&self, tier: u8) -> Result<LockTier> {
+        let lock_tier = *self
+            .lock_tiers
+            .get(tier as usize)
+            .ok_or(StakingError::InvalidLockTier)?;
+        require!(lock_tier.multiplier_bps > 0, StakingError::InvalidLockTier);
+        Ok(lock_tier)
+    }
+
+    //  Rarity weight of NFT proved against weight root, all NFTs weigh the same until root is published
+    pub fn rarity_weight(&self, mint: &Pubkey, weight: u16, proof: &[[u8; 32]]) -> Result<u16> {
+        if self.weight_root == [0u8; 32] {
+            return Ok(DEFAULT_NFT_WEIGHT);
+        }
+        let leaf = keccak::hashv(&[mint.as_ref(), &weight.to_le_bytes()]);
+        require!(
+            weight > 0 && verify_merkle_proof(proof, self.weight_root, leaf.0),
+            StakingError::InvalidWeightProof
+        );
+        Ok(weight)
+    }
+
+    //  Highest streak bonus reached after streak days of staking
+    pub fn streak_bonus(&self, streak_days: i64) -> u16 {
+        self.streak_steps
+            .iter()
+            .filter(|step| step.bonus_bps > 0 && step.days as i64 <= streak_days)
+            .map(|step| step.bonus_bps)
+            .max()
+            .unwrap_or(0)
+    }
+
+    //  Streak bonus averaged over a settle period, each step only counts after its threshold
+    pub fn streak_bonus_over(&self, streak_start: i64, from: i64, to: i64) -> u16 {
+        if streak_start == 0 || to <= from {
+            return 0;
+        }
+
+        // Split period where steps are reached, bonus level is fixed within each part
+        let mut start = from.max(streak_start);
+        let mut ends: Vec<i64> = self
+            .streak_steps
+            .iter()
+            .filter(|step| step.bonus_bps > 0)
+            .map(|step| streak_start + step.days as i64 * DAY_SECONDS)
+            .filter(|reached_at| *reached_at > start && *reached_at < to)
+            .collect();
+        ends.push(to);
+        ends.sort_unstable();
+
+        let mut weighted = 0u128;
+        for end in ends {
+            let bonus = self.streak_bonus((start - streak_start) / DAY_SECONDS);
+            weighted += bonus as u128 * (end - start) as u128;
+            start = end;
+        }
+        (weighted / (to - from) as u128) as u16
+    }
+
+    pub fn is_points_spender(&self, program: &Pubkey) -> bool {
+        *program != Pubkey::default() && self.points_spenders.contains(program)
+    }
+
+    pub fn is_marketplace(&self, program: &Pubkey) -> bool {
+        *program != Pubkey::default() && self.marketplaces.contains(program)
+    }
+
+    pub fn apply_config_change(&mut self, change: &ConfigChange, timestamp: i64) -> Result<()> {
+        match *change {
+            ConfigChange::Admin { new_admin } => {
+                self.admin = new_admin;
+            }
+            ConfigChange::ConfigDelay { delay } => {
+                self.config_delay = delay;
+            }
+            ConfigChange::Fees { lock_fee, unlock_fee } => {
+                self.lock_fee = lock_fee;
+                self.unlock_fee = unlock_fee;
+            }
+            ConfigChange::UnbondingPeriod { period } => {
+                self.unbonding_period = period;
+            }
+            ConfigChange::LockTier { index, tier } => {
+                self.lock_tiers[index as usize] = tier;
+            }
+            ConfigChange::WeightRoot { root } => {
+                self.weight_root = root;
+            }
+            ConfigChange::RewardRate { index, rate } => {
+                require!(index < self.stream_cnt, StakingError::InvalidRewardStream);
+                // Rewards so far accrue at the old rate
+                self.update_rewards(timestamp);
+                self.reward_streams[index as usize].rate = rate;
+            }
+            ConfigChange::PointsRate { rate } => {
+                // Points so far accrue at the old rate
+                self.update_points(timestamp);
+                self.points_rate = rate;
+            }
+            ConfigChange::PointsSpender { index, program } => {
+                self.points_spenders[index as usize] = program;
+            }
+            ConfigChange::Marketplace { index, program } => {
+                self.marketplaces[index as usize] = program;
+            }
+            ConfigChange::StreakStep { index, step } => {
+                self.streak_steps[index as usize] = step;
+            }
+            ConfigChange::CrankTip { tip_bps } => {
+                self.crank_tip_bps = tip_bps;
+            }
+            //  Applies to rewards claimed from now on, existing schedules keep their end time
+            ConfigChange::VestingDuration { duration } => {
+                self.vesting_duration = duration;
+            }
+            ConfigChange::StakeMint { mint } => {
+                require!(self.total_staked_amount == 0, StakingError::TokensStaked);
+                self.stake_mint = mint;
+            }
+            //  Existing token stakes are reweighted on their next change
+            ConfigChange::TokenWeightRatio { ratio } => {
+                self.token_weight_ratio = ratio;
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Lock commitment tier, NFT can't be unlocked before duration ends
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LockTier {
+    pub duration_days: u16,     //  2
+    pub multiplier_bps: u16,    //  2
+}
+
+impl LockTier {
+    pub const DATA_SIZE: usize = 2 + 2;
+}
+
+/**
+ * Streak step gives reward bonus once wallet kept staking for days
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StreakStep {
+    pub days: u16,      //  2
+    pub bonus_bps: u16, //  2
+}
+
+impl StreakStep {
+    pub const DATA_SIZE: usize = 2 + 2;
+}
+
+/**
+ * Reward stream pays reward token from its vault to stakers
+ * rate is amount per second for one NFT of weight 1 at base multiplier
+ * emission stops at end time or once funded amount is emitted
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RewardStream {
+    pub mint: Pubkey,       //  32
+    pub rate: u64,          //  8
+    pub start_time: i64,    //  8
+    pub end_time: i64,      //  8
+    pub acc_reward_per_weight: u128,    //  16
+    pub last_update_time: i64,  //  8
+    pub funded: u64,            //  8
+    pub emitted: u64,           //  8
+    pub claimed: u64,           //  8
+}
+
+impl RewardStream {
+    pub const DATA_SIZE: usize = 32 + 8 + 8 + 8 + 16 + 8 + 8 + 8 + 8;
+
+    //  Accumulate reward per base weight since last update, limited to stream period and funds
+    pub fn update(&mut self, total_stake_weight: u64, timestamp: i64) {
+        let from = self.last_update_time.max(self.start_time);
+        let to = timestamp.min(self.end_time);
+        if to > from && total_stake_weight > 0 {
+            let emission = (self.rate as u128 * (to - from) as u128)
+                .min(self.funded.saturating_sub(self.emitted) as u128);
+            self.acc_reward_per_weight += emission
+                * REWARD_PRECISION
+                * BASE_MULTIPLIER as u128
+                / total_stake_weight as u128;
+            self.emitted += emission as u64;
+        }
+        self.last_update_time = self.last_update_time.max(timestamp);
+    }
+
+    //  Rewards accrued to users but not claimed yet
+    pub fn obligation(&self) -> u64 {
+        self.emitted - self.claimed
+    }
+
+    //  Reward of weight accumulated so far, in reward token amount
+    pub fn accumulated(&self, weight: u64) -> u128 {
+        weight as u128 * self.acc_reward_per_weight / REWARD_PRECISION / BASE_MULTIPLIER as u128
+    }
+}
+
+/**
+ * Config values admin can change through the timelock
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum ConfigChange {
+    Admin { new_admin: Pubkey },
+    ConfigDelay { delay: i64 },
+    Fees { lock_fee: u64, unlock_fee: u64 },
+    UnbondingPeriod { period: i64 },
+    LockTier { index: u8, tier: LockTier },
+    RewardRate { index: u8, rate: u64 },
+    WeightRoot { root: [u8; 32] },
+    PointsRate { rate: u64 },
+    //  Default pubkey clears the slot
+    PointsSpender { index: u8, program: Pubkey },
+    //  Default pubkey clears the slot
+    Marketplace { index: u8, program: Pubkey },
+    StreakStep { index: u8, step: StreakStep },
+    CrankTip { tip_bps: u16 },
+    VestingDuration { duration: i64 },
+    StakeMint { mint: Pubkey },
+    TokenWeightRatio { ratio: u64 },
+}
+
+impl Default for ConfigChange {
+    fn default() -> Self {
+        ConfigChange::ConfigDelay { delay: DEFAULT_CONFIG_DELAY }
+    }
+}
+
+impl ConfigChange {
+    //  1 byte variant tag + largest variant
+    pub const MAX_SIZE: usize = 1 + 33;
+
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            ConfigChange::ConfigDelay { delay } => {
+                require!(
+                    (0..=MAX_CONFIG_DELAY).contains(&delay),
+                    StakingError::InvalidConfig
+                );
+            }
+            ConfigChange::UnbondingPeriod { period } => {
+                require!(
+                    (0..=MAX_UNBONDING_PERIOD).contains(&period),
+                    StakingError::InvalidConfig
+                );
+            }
+            ConfigChange::LockTier { index, tier } => {
+                require!(
+                    (index as usize) < MAX_LOCK_TIERS,
+                    StakingError::InvalidLockTier
+                );
+                // Zero multiplier disables the tier
+                require!(
+                    tier.duration_days <= MAX_LOCK_DAYS && tier.multiplier_bps <= MAX_MULTIPLIER,
+                    StakingError::InvalidConfig
+                );
+            }
+            ConfigChange::StreakStep { index, step } => {
+                require!(
+                    (index as usize) < MAX_STREAK_STEPS && step.bonus_bps <= MAX_STREAK_BONUS,
+                    StakingError::InvalidConfig
+                );
+            }
+            ConfigChange::VestingDuration { duration } => {
+                require!(
+                    (0..=MAX_VESTING_DURATION).contains(&duration),
+                    StakingError::InvalidConfig
+                );
+            }
+            ConfigChange::CrankTip { tip_bps } => {
+                require!(tip_bps <= MAX_CRANK_TIP_BPS, StakingError::InvalidConfig);
+            }
+            ConfigChange::PointsSpender { index, .. } => {
+                require!(
+                    (index as usize) < MAX_POINTS_SPENDERS,
+                    StakingError::InvalidConfig
+                );
+            }
+            ConfigChange::Marketplace { index, .. } => {
+                require!(
+                    (index as usize) < MAX_MARKETPLACES,
+                    StakingError::InvalidConfig
+                );
+            }
+            ConfigChange::RewardRate { index, .. } => {
+                require!(
+                    (index as usize) < MAX_REWARD_STREAMS,
+                    StakingError::InvalidRewardStream
+                );
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Pending change stores a queued config change until it can be executed
+ */
+#[account]
+#[derive(Default)]
+pub struct PendingChange {
+    pub id: u64,                //  8
+    pub change: ConfigChange,   //  ConfigChange::MAX_SIZE
+    pub queued_at: i64,         //  8
+    pub eta: i64,               //  8
+}
+
+impl PendingChange {
+    pub const DATA_SIZE: usize = 8 + ConfigChange::MAX_SIZE + 8 + 8;
+
+    //  Queue validated change, it can be executed once config delay passed
+    pub fn queue(
+        &mut self,
+        global_pool: &mut GlobalPool,
+        change: ConfigChange,
+        timestamp: i64,
+    ) -> Result<()> {
+        change.validate()?;
+
+        self.id = global_pool.change_cnt;
+        self.change = change;
+        self.queued_at = timestamp;
+        self.eta = timestamp + global_pool.config_delay;
+
+        global_pool.change_cnt += 1;
+        Ok(())
+    }
+
+    //  Apply queued change to global pool after its eta
+    pub fn execute(&self, global_pool: &mut GlobalPool, timestamp: i64) -> Result<()> {
+        require!(timestamp >= self.eta, StakingError::TimelockNotExpired);
+        global_pool.apply_config_change(&self.change, timestamp)
+    }
+}
+
+/**
+ * Blocklist entry of a wallet or a NFT mint
+ * PDA existence means the address is blocked
+ */
+#[account]
+#[derive(Default)]
+pub struct BlocklistEntry {
+    pub address: Pubkey,    //  32
+    pub blocked_at: i64,    //  8
+}
+
+impl BlocklistEntry {
+    pub const DATA_SIZE: usize = 32 + 8;
+}
+
+/**
+ * How a staked NFT is held while staked
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Custody {
+    //  pNFT locked in owner wallet by token metadata staking delegate
+    #[default]
+    TokenMetadataLock,
+    //  Token-2022 NFT moved to escrow owned by user pool
+    Escrow,
+    //  Non-transferable Token-2022 NFT stays in owner wallet, no longer accepted but kept for layout
+    InPlace,
+    //  Metaplex Core asset frozen by freeze delegate plugin of user pool
+    CoreFreeze,
+    //  Compressed NFT leaf owned by user pool
+    CompressedEscrow,
+    //  pNFT moved to global pool, position is held by receipt token holder
+    ReceiptEscrow,
+}
+
+/**
+ * Staked NFT stores stake info of a locked NFT
+ */
+#[account]
+#[derive(Default)]
+pub struct StakedNft {
+    pub owner: Pubkey,              //  32
+    pub mint: Pubkey,               //  32
+    pub staked_at: i64,             //  8
+    pub unlock_requested_at: i64,   //  8
+    pub lock_end: i64,              //  8
+    pub multiplier_bps: u16,        //  2
+    pub rarity_weight: u16,         //  2
+    pub weight: u64,                //  8
+    pub custody: Custody,           //  1
+    pub reward_debts: [u128; MAX_REWARD_STREAMS],   //  16 * 4  receipt positions only
+    pub receipt_mint: Pubkey,       //  32  receipt positions only, new mint for every position
+}
+
+impl StakedNft {
+    pub const DATA_SIZE: usize = 32 + 32 + 8 + 8 + 8 + 2 + 2 + 8 + 1
+        + 16 * MAX_REWARD_STREAMS + 32;
+
+    pub fn is_unbonding(&self) -> bool {
+        self.unlock_requested_at != 0
+    }
+
+    /**
+     * Exit checks shared by every custody type
+     * requested unlock completes after unbonding period, otherwise one-step exit needs no unbonding period
+     * user can't exit while a poll they voted in is running
+     */
+    pub fn check_unlock(
+        &self,
+        global_pool: &GlobalPool,
+        vote_lock_end: i64,
+        timestamp: i64,
+    ) -> Result<()> {
+        require!(timestamp >= vote_lock_end, StakingError::VoteActive);
+        if self.is_unbonding() {
+            require!(
+                timestamp >= self.unlock_requested_at + global_pool.unbonding_period,
+                StakingError::StillLocked
+            );
+        } else {
+            require!(
+                global_pool.unbonding_period == 0,
+                StakingError::UnbondingRequired
+            );
+            require!(timestamp >= self.lock_end, StakingError::StillLocked);
+        }
+        Ok(())
+    }
+
+    /**
+     * Receipt position earns reward streams on its own instead of through a user pool
+     * it isn't in any stake count, so points, revenue, voting and snapshots leave it out
+     */
+    pub fn open_position(&mut self, global_pool: &mut GlobalPool, timestamp: i64) {
+        global_pool.update_rewards(timestamp);
+        global_pool.total_stake_weight += self.weight;
+        global_pool.receipt_weight += self.weight;
+        for (index, stream) in global_pool.reward_streams[..global_pool.stream_cnt as usize]
+            .iter()
+            .enumerate()
+        {
+            self.reward_debts[index] = stream.accumulated(self.weight);
+        }
+    }
+
+    //  Move pending rewards of receipt position to holder's user pool
+    pub fn harvest_position(
+        &mut self,
+        global_pool: &mut GlobalPool,
+        holder_pool: &mut UserPool,
+        timestamp: i64,
+    ) {
+        global_pool.update_rewards(timestamp);
+        for (index, stream) in global_pool.reward_streams[..global_pool.stream_cnt as usize]
+            .iter()
+            .enumerate()
+        {
+            let accumulated = stream.accumulated(self.weight);
+            holder_pool.rewards[index] +=
+                accumulated.saturating_sub(self.reward_debts[index]) as u64;
+            self.reward_debts[index] = accumulated;
+        }
+    }
+
+    //  Harvest receipt position and remove its weight
+    pub fn close_position(
+        &mut self,
+        global_pool: &mut GlobalPool,
+        holder_pool: &mut UserPool,
+        timestamp: i64,
+    ) {
+        self.harvest_position(global_pool, holder_pool, timestamp);
+        global_pool.total_stake_weight -= self.weight;
+        global_pool.receipt_weight -= self.weight;
+    }
+
+    //  Remove weight of receipt position, its pending rewards are forfeited
+    pub fn forfeit_position(&mut self, global_pool: &mut GlobalPool, timestamp: i64) {
+        global_pool.update_rewards(timestamp);
+        global_pool.total_stake_weight -= self.weight;
+        global_pool.receipt_weight -= self.weight;
+    }
+
+    pub fn stake(
+        &mut self,
+        owner: Pubkey,
+        mint: Pubkey,
+        lock_tier: LockTier,
+        rarity_weight: u16,
+        custody: Custody,
+        timestamp: i64,
+    ) {
+        self.owner = owner;
+        self.mint = mint;
+        self.staked_at = timestamp;
+        self.lock_end = timestamp + lock_tier.duration_days as i64 * DAY_SECONDS;
+        self.multiplier_bps = lock_tier.multiplier_bps;
+        self.rarity_weight = rarity_weight;
+        self.weight = rarity_weight as u64 * lock_tier.multiplier_bps as u64;
+        self.custody = custody;
+    }
+}
+
+/**
+ * Store item users can redeem with points
+ * zero per wallet limit means no limit
+ */
+#[account]
+#[derive(Default)]
+pub struct StoreItem {
+    pub id: u32,                //  4
+    pub price: u64,             //  8
+    pub supply: u32,            //  4
+    pub sold: u32,              //  4
+    pub per_wallet_limit: u32,  //  4
+    pub start_time: i64,        //  8
+    pub end_time: i64,          //  8
+}
+
+impl StoreItem {
+    pub const DATA_SIZE: usize = 4 + 8 + 4 + 4 + 4 + 8 + 8;
+}
+
+/**
+ * Redemption receipt of a user for a store item, read by fulfillment service
+ */
+#[account]
+#[derive(Default)]
+pub struct Redemption {
+    pub item: Pubkey,           //  32
+    pub user: Pubkey,           //  32
+    pub quantity: u32,          //  4
+    pub points_spent: u64,      //  8
+    pub last_redeemed_at: i64,  //  8
+}
+
+impl Redemption {
+    pub const DATA_SIZE: usize = 32 + 32 + 4 + 8 + 8;
+}
+
+/**
+ * Vesting schedule of claimed rewards of a stream
+ * locked amount releases linearly until end time
+ */
+#[account]
+#[derive(Default)]
+pub struct Vesting {
+    pub user: Pubkey,       //  32
+    pub stream_index: u8,   //  1
+    pub locked: u64,        //  8
+    pub released: u64,      //  8  released but not withdrawn yet
+    pub start_time: i64,    //  8  last release checkpoint
+    pub end_time: i64,      //  8
+}
+
+impl Vesting {
+    pub const DATA_SIZE: usize = 32 + 1 + 8 + 8 + 8 + 8;
+
+    //  Move locked amount vested since last checkpoint to released
+    pub fn release(&mut self, timestamp: i64) {
+        if self.locked > 0 && timestamp > self.start_time {
+            let vested = if timestamp >= self.end_time {
+                self.locked
+            } else {
+                (self.locked as u128 * (timestamp - self.start_time) as u128
+                    / (self.end_time - self.start_time) as u128) as u64
+            };
+            self.locked -= vested;
+            self.released += vested;
+        }
+        self.start_time = self.start_time.max(timestamp);
+    }
+
+    //  Merge claimed amount into schedule, end time is weighted by amount and remaining time
+    pub fn add(&mut self, amount: u64, duration: i64, timestamp: i64) {
+        self.release(timestamp);
+        if duration == 0 {
+            self.released += amount;
+            return;
+        }
+
+        let remaining = (self.end_time - timestamp).max(0) as u128;
+        let weighted_duration = (self.locked as u128 * remaining
+            + amount as u128 * duration as u128)
+            / (self.locked + amount) as u128;
+        self.locked += amount;
+        self.start_time = timestamp;
+        self.end_time = timestamp + weighted_duration as i64;
+    }
+}
+
+/**
+ * Staker poll, option labels are published off-chain by poll id
+ */
+#[account]
+#[derive(Default)]
+pub struct Poll {
+    pub id: u32,            //  4
+    pub option_cnt: u8,     //  1
+    pub votes: [u64; MAX_POLL_OPTIONS], //  8 * 8  stake count voted for each option
+    pub start_time: i64,    //  8
+    pub end_time: i64,      //  8
+}
+
+impl Poll {
+    pub const DATA_SIZE: usize = 4 + 1 + 8 * MAX_POLL_OPTIONS + 8 + 8;
+}
+
+/**
+ * Vote of a user in a poll
+ * PDA existence means the user already voted
+ */
+#[account]
+#[derive(Default)]
+pub struct VoteRecord {
+    pub poll: Pubkey,       //  32
+    pub user: Pubkey,       //  32
+    pub option: u8,         //  1
+    pub weight: u64,        //  8
+    pub voted_at: i64,      //  8
+}
+
+impl VoteRecord {
+    pub const DATA_SIZE: usize = 32 + 32 + 1 + 8 + 8;
+}
+
+/**
+ * Snapshot opens a new epoch, its boundary is the time it was taken
+ */
+#[account]
+#[derive(Default)]
+pub struct Snapshot {
+    pub epoch: u32,                 //  4
+    pub taken_at: i64,              //  8
+    pub slot: u64,                  //  8
+    pub total_stake_cnt: u64,       //  8
+    pub total_stake_weight: u64,    //  8
+}
+
+impl Snapshot {
+    pub const DATA_SIZE: usize = 4 + 8 + 8 + 8 + 8;
+}
+
+/**
+ * Stake count of a user at boundaries of epochs from from_epoch to to_epoch
+ * written lazily on the first stake change after those snapshots
+ */
+#[account]
+#[derive(Default)]
+pub struct StakeCheckpoint {
+    pub user: Pubkey,       //  32
+    pub from_epoch: u32,    //  4
+    pub to_epoch: u32,      //  4
+    pub stake_cnt: u16,     //  2
+}
+
+impl StakeCheckpoint {
+    pub const DATA_SIZE: usize = 32 + 4 + 4 + 2;
+}
+
+/**
+ * Merkle airdrop distribution, leaves are keccak(index, user, amount)
+ */
+#[account]
+#[derive(Default)]
+pub struct Distribution {
+    pub id: u32,                //  4
+    pub mint: Pubkey,           //  32
+    pub root: [u8; 32],         //  32
+    pub total_amount: u64,      //  8
+    pub claimed_amount: u64,    //  8
+    pub leaf_cnt: u32,          //  4
+    pub claimed: Vec<u8>,       //  4 + bitmap of leaf_cnt bits
+}
+
+impl Distribution {
+    pub const DATA_SIZE: usize = 4 + 32 + 32 + 8 + 8 + 4 + 4;
+
+    pub fn bitmap_size(leaf_cnt: u32) -> usize {
+        (leaf_cnt as usize).div_ceil(8)
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u32) {
+        self.claimed[index as usize / 8] |= 1 << (index % 8);
+    }
 }
 
 /**
@@ -19,10 +802,814 @@ impl GlobalPool {
 #[account]
 #[derive(Default)]
 pub struct UserPool {
-    pub user: Pubkey,   // 32
-    pub stake_cnt: u16, // 2
+    pub user: Pubkey,       // 32
+    pub stake_cnt: u16,     // 2
+    pub stake_weight: u64,  // 8  sum of rarity weight * tier multiplier and token weight
+    pub rewards: [u64; MAX_REWARD_STREAMS], // 8 * 4
+    pub reward_debts: [u128; MAX_REWARD_STREAMS],   // 16 * 4
+    pub points: u64,        // 8
+    pub points_index: u64,  // 8
+    pub streak_start: i64,  // 8  zero while nothing is staked
+    pub reward_recipient: Option<Pubkey>,   // 33
+    pub revenue: u64,           // 8  lamports
+    pub revenue_index: u128,    // 16
+    pub vote_lock_end: i64,     // 8  unstaking is blocked until polls user voted in end
+    pub checkpoint_epoch: u32,  // 4  stake count holds since this epoch's boundary
+    pub staked_amount: u64,     // 8  staked fungible tokens
+    pub token_weight: u64,      // 8  part of stake weight from staked tokens
+    pub version: u8,            // 1  layout version, zero before migration
+    pub last_settled_at: i64,   // 8
 }
 
 impl UserPool {
-    pub const DATA_SIZE: usize = 32 + 2;
+    pub const DATA_SIZE: usize = 32 + 2 + 8 + 8 * MAX_REWARD_STREAMS + 16 * MAX_REWARD_STREAMS
+        + 8 + 8 + 8 + 33
+        + 8 + 16
+        + 8 + 4
+        + 8 + 8
+        + 1 + 8;
+
+    //  Rewards are sent to recipient if user set one
+    pub fn reward_destination(&self) -> Pubkey {
+        self.reward_recipient.unwrap_or(self.user)
+    }
+
+    //  Take settled rewards of a stream up to available vault balance, remainder stays owed
+    pub fn take_reward(
+        &mut self,
+        global_pool: &mut GlobalPool,
+        stream_index: u8,
+        available: u64,
+    ) -> Result<u64> {
+        require!(
+            stream_index < global_pool.stream_cnt,
+            StakingError::InvalidRewardStream
+        );
+        let owed = self.rewards[stream_index as usize];
+        require!(owed > 0, StakingError::NoReward);
+
+        let amount = owed.min(available);
+        require!(amount > 0, StakingError::RewardVaultEmpty);
+        self.rewards[stream_index as usize] = owed - amount;
+        global_pool.reward_streams[stream_index as usize].claimed += amount;
+
+        Ok(amount)
+    }
+
+    //  Take all settled rewards of a stream into vesting, vault balance is checked on withdrawal
+    pub fn take_reward_for_vesting(
+        &mut self,
+        global_pool: &GlobalPool,
+        stream_index: u8,
+    ) -> Result<u64> {
+        require!(
+            stream_index < global_pool.stream_cnt,
+            StakingError::InvalidRewardStream
+        );
+        let owed = self.rewards[stream_index as usize];
+        require!(owed > 0, StakingError::NoReward);
+        self.rewards[stream_index as usize] = 0;
+
+        Ok(owed)
+    }
+
+    //  Checkpoint global accumulators and accrue user rewards and points, must run before stake changes
+    pub fn settle_rewards(&mut self, global_pool: &mut GlobalPool, timestamp: i64) -> Result<()> {
+        self.settle_rewards_forfeiting(global_pool, timestamp, 0)
+    }
+
+    //  Same as settle_rewards, but pending rewards and points of the forfeited NFT are dropped
+    pub fn settle_rewards_forfeiting(
+        &mut self,
+        global_pool: &mut GlobalPool,
+        timestamp: i64,
+        forfeit_weight: u64,
+    ) -> Result<()> {
+        global_pool.update_rewards(timestamp);
+
+        // Streak bonus only applies to the part of pending period after each step is reached
+        let streak_bonus =
+            global_pool.streak_bonus_over(self.streak_start, self.last_settled_at, timestamp);
+        let stream_cnt = global_pool.stream_cnt as usize;
+        for (index, stream) in global_pool.reward_streams[..stream_cnt]
+            .iter_mut()
+            .enumerate()
+        {
+            let pending = stream
+                .accumulated(self.stake_weight)
+                .saturating_sub(self.reward_debts[index]);
+            let kept = if self.stake_weight > 0 {
+                pending * (self.stake_weight - forfeit_weight) as u128 / self.stake_weight as u128
+            } else {
+                0
+            };
+            // Bonus is paid from the same vault on top of emission, limited by funds left
+            let bonus = (kept * streak_bonus as u128 / BASE_MULTIPLIER as u128)
+                .min(stream.funded.saturating_sub(stream.emitted) as u128);
+            stream.emitted += bonus as u64;
+            self.rewards[index] += (kept + bonus) as u64;
+        }
+        self.update_reward_debts(global_pool);
+
+        // Forfeit weight always belongs to a single NFT
+        global_pool.update_points(timestamp);
+        let forfeit_cnt = if forfeit_weight > 0 { 1 } else { 0 };
+        self.points = self.points.saturating_add(
+            ((self.stake_cnt - forfeit_cnt) as u64)
+                .saturating_mul(global_pool.points_per_nft - self.points_index),
+        );
+        self.points_index = global_pool.points_per_nft;
+
+        // Revenue of forfeited NFT goes back to all stakers
+        let revenue_delta = global_pool.revenue_per_nft - self.revenue_index;
+        self.revenue = self.revenue.saturating_add(
+            ((self.stake_cnt - forfeit_cnt) as u128 * revenue_delta / REWARD_PRECISION) as u64,
+        );
+        global_pool.revenue_carry = global_pool.revenue_carry.saturating_add(
+            (forfeit_cnt as u128 * revenue_delta / REWARD_PRECISION) as u64,
+        );
+        self.revenue_index = global_pool.revenue_per_nft;
+        self.last_settled_at = timestamp;
+
+        // Staked tokens are weighted at current ratio from now on, even if it changed since staking
+        if self.staked_amount > 0 {
+            self.set_token_stake(global_pool, self.staked_amount)?;
+        }
+        Ok(())
+    }
+
+    fn update_reward_debts(&mut self, global_pool: &GlobalPool) {
+        for (index, stream) in global_pool.reward_streams[..global_pool.stream_cnt as usize]
+            .iter()
+            .enumerate()
+        {
+            self.reward_debts[index] = stream.accumulated(self.stake_weight);
+        }
+    }
+
+    //  Record stake count held at snapshots taken since last change, must run before stake changes
+    pub fn checkpoint_stake(&mut self, global_pool: &GlobalPool, checkpoint: &mut StakeCheckpoint) {
+        if global_pool.epoch > self.checkpoint_epoch {
+            checkpoint.user = self.user;
+            checkpoint.from_epoch = self.checkpoint_epoch + 1;
+            checkpoint.to_epoch = global_pool.epoch;
+            checkpoint.stake_cnt = self.stake_cnt;
+            self.checkpoint_epoch = global_pool.epoch;
+        }
+    }
+
+    //  Add a staked NFT, rewards must be settled first
+    pub fn add_stake(&mut self, global_pool: &mut GlobalPool, weight: u64, timestamp: i64) {
+        if self.stake_cnt == 0 {
+            self.streak_start = timestamp;
+        }
+        self.stake_cnt += 1;
+        self.stake_weight += weight;
+        global_pool.total_stake_cnt += 1;
+        global_pool.total_stake_weight += weight;
+        self.update_reward_debts(global_pool);
+    }
+
+    //  Set staked token amount weighted at current ratio, rewards must be settled first
+    pub fn set_token_stake(&mut self, global_pool: &mut GlobalPool, amount: u64) -> Result<()> {
+        let token_weight = global_pool.token_weight(amount)?;
+        global_pool.total_staked_amount =
+            global_pool.total_staked_amount - self.staked_amount + amount;
+        global_pool.total_stake_weight =
+            global_pool.total_stake_weight - self.token_weight + token_weight;
+        self.stake_weight = self.stake_weight - self.token_weight + token_weight;
+        self.staked_amount = amount;
+        self.token_weight = token_weight;
+        self.update_reward_debts(global_pool);
+        Ok(())
+    }
+
+    //  Remove a staked NFT, rewards must be settled first
+    pub fn remove_stake(&mut self, global_pool: &mut GlobalPool, weight: u64) -> Result<()> {
+        self.stake_cnt = self.stake_cnt.checked_sub(1).ok_or(StakingError::MathOverflow)?;
+        self.stake_weight = self
+            .stake_weight
+            .checked_sub(weight)
+            .ok_or(StakingError::MathOverflow)?;
+        global_pool.total_stake_cnt = global_pool
+            .total_stake_cnt
+            .checked_sub(1)
+            .ok_or(StakingError::MathOverflow)?;
+        global_pool.total_stake_weight = global_pool
+            .total_stake_weight
+            .checked_sub(weight)
+            .ok_or(StakingError::MathOverflow)?;
+        self.update_reward_debts(global_pool);
+
+        // Full exit breaks the streak
+        if self.stake_cnt == 0 {
+            self.streak_start = 0;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Action voter weight is evaluated for, as defined by spl-governance add-in interface
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VoterWeightAction {
+    CastVote,
+    CommentProposal,
+    CreateGovernance,
+    CreateProposal,
+    SignOffProposal,
+}
+
+/**
+ * Voter weight record read by spl-governance, layout follows the voter weight add-in interface
+ * so it uses the interface discriminator instead of anchor account discriminator
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct VoterWeightRecord {
+    pub realm: Pubkey,                  //  32
+    pub governing_token_mint: Pubkey,   //  32
+    pub governing_token_owner: Pubkey,  //  32
+    pub voter_weight: u64,              //  8
+    pub voter_weight_expiry: Option<u64>,   //  9
+    pub weight_action: Option<VoterWeightAction>,   //  2
+    pub weight_action_target: Option<Pubkey>,   //  33
+    pub reserved: [u8; 8],              //  8
+}
+
+impl VoterWeightRecord {
+    pub const DATA_SIZE: usize = 32 + 32 + 32 + 8 + 9 + 2 + 33 + 8;
+}
+
+impl Discriminator for VoterWeightRecord {
+    //  sha256("account:VoterWeightRecord")[..8]
+    const DISCRIMINATOR: [u8; 8] = [46, 249, 155, 75, 153, 248, 116, 9];
+}
+
+impl Owner for VoterWeightRecord {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl AccountSerialize for VoterWeightRecord {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        writer
+            .write_all(&Self::DISCRIMINATOR)
+            .map_err(|_| ErrorCode::AccountDidNotSerialize)?;
+        AnchorSerialize::serialize(self, writer).map_err(|_| ErrorCode::AccountDidNotSerialize)?;
+        Ok(())
+    }
+}
+
+impl AccountDeserialize for VoterWeightRecord {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < 8 || buf[..8] != Self::DISCRIMINATOR {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let mut data: &[u8] = &buf[8..];
+        AnchorDeserialize::deserialize(&mut data)
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+//  Bubblegum leaf metadata, same layout as bubblegum MetadataArgs so it hashes to the leaf data hash
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenStandard {
+    NonFungible,
+    FungibleAsset,
+    Fungible,
+    NonFungibleEdition,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UseMethod {
+    Burn,
+    Multiple,
+    Single,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Uses {
+    pub use_method: UseMethod,
+    pub remaining: u64,
+    pub total: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenProgramVersion {
+    Original,
+    Token2022,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MetadataArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<TokenStandard>,
+    pub collection: Option<Collection>,
+    pub uses: Option<Uses>,
+    pub token_program_version: TokenProgramVersion,
+    pub creators: Vec<Creator>,
+}
+
+impl MetadataArgs {
+    //  Data hash of the leaf
+    pub fn data_hash(&self) -> Result<[u8; 32]> {
+        let metadata_args_hash = keccak::hashv(&[self.try_to_vec()?.as_slice()]);
+        Ok(keccak::hashv(&[
+            &metadata_args_hash.to_bytes(),
+            &self.seller_fee_basis_points.to_le_bytes(),
+        ])
+        .to_bytes())
+    }
+
+    //  Creator hash of the leaf
+    pub fn creator_hash(&self) -> [u8; 32] {
+        let creator_data = self
+            .creators
+            .iter()
+            .map(|creator| {
+                [
+                    creator.address.as_ref(),
+                    &[creator.verified as u8],
+                    &[creator.share],
+                ]
+                .concat()
+            })
+            .collect::<Vec<_>>();
+        keccak::hashv(
+            &creator_data
+                .iter()
+                .map(|data| data.as_slice())
+                .collect::<Vec<&[u8]>>(),
+        )
+        .to_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, anchor_lang::solana_program::hash::hash};
+
+    #[test]
+    fn voter_weight_record_discriminator_matches_governance() {
+        let expected = hash(b"account:VoterWeightRecord").to_bytes();
+        assert_eq!(VoterWeightRecord::DISCRIMINATOR, expected[..8]);
+    }
+
+    #[test]
+    fn config_change_waits_for_eta() {
+        let mut global_pool = GlobalPool {
+            config_delay: 100,
+            ..Default::default()
+        };
+        let mut pending_change = PendingChange::default();
+        pending_change
+            .queue(
+                &mut global_pool,
+                ConfigChange::Fees { lock_fee: 5, unlock_fee: 7 },
+                1_000,
+            )
+            .unwrap();
+        assert_eq!(pending_change.eta, 1_100);
+        assert_eq!(global_pool.change_cnt, 1);
+
+        assert!(pending_change.execute(&mut global_pool, 1_099).is_err());
+        assert_eq!(global_pool.lock_fee, 0);
+
+        pending_change.execute(&mut global_pool, 1_100).unwrap();
+        assert_eq!(global_pool.lock_fee, 5);
+        assert_eq!(global_pool.unlock_fee, 7);
+    }
+
+    #[test]
+    fn new_config_delay_applies_to_later_changes() {
+        let mut global_pool = GlobalPool {
+            config_delay: 100,
+            ..Default::default()
+        };
+        let mut delay_change = PendingChange::default();
+        delay_change
+            .queue(&mut global_pool, ConfigChange::ConfigDelay { delay: 500 }, 0)
+            .unwrap();
+        delay_change.execute(&mut global_pool, 100).unwrap();
+
+        let mut fee_change = PendingChange::default();
+        fee_change
+            .queue(
+                &mut global_pool,
+                ConfigChange::Fees { lock_fee: 1, unlock_fee: 1 },
+                200,
+            )
+            .unwrap();
+        assert_eq!(fee_change.id, 1);
+        assert_eq!(fee_change.eta, 700);
+    }
+
+    #[test]
+    fn invalid_config_change_is_not_queued() {
+        let mut global_pool = GlobalPool::default();
+        let mut pending_change = PendingChange::default();
+        assert!(pending_change
+            .queue(
+                &mut global_pool,
+                ConfigChange::ConfigDelay { delay: MAX_CONFIG_DELAY + 1 },
+                0,
+            )
+            .is_err());
+        assert_eq!(global_pool.change_cnt, 0);
+    }
+
+    //  Global pool with one stream running from zero until far future
+    fn pool_with_stream(rate: u64, funded: u64) -> GlobalPool {
+        let mut global_pool = GlobalPool {
+            stream_cnt: 1,
+            ..Default::default()
+        };
+        global_pool.reward_streams[0] = RewardStream {
+            rate,
+            end_time: i64::MAX,
+            funded,
+            ..Default::default()
+        };
+        global_pool
+    }
+
+    #[test]
+    fn reward_rate_change_is_not_retroactive() {
+        let mut global_pool = pool_with_stream(10, u64::MAX);
+        let mut user_pool = UserPool::default();
+        user_pool.settle_rewards(&mut global_pool, 0).unwrap();
+        user_pool.add_stake(&mut global_pool, BASE_MULTIPLIER as u64, 0);
+
+        global_pool
+            .apply_config_change(&ConfigChange::RewardRate { index: 0, rate: 30 }, 100)
+            .unwrap();
+        user_pool.settle_rewards(&mut global_pool, 200).unwrap();
+
+        // 100 seconds at old rate, then 100 seconds at new rate
+        assert_eq!(user_pool.rewards[0], 10 * 100 + 30 * 100);
+    }
+
+    #[test]
+    fn rewards_split_by_stake_weight() {
+        let mut global_pool = pool_with_stream(100, u64::MAX);
+        let mut small = UserPool::default();
+        let mut large = UserPool::default();
+        small.settle_rewards(&mut global_pool, 0).unwrap();
+        small.add_stake(&mut global_pool, BASE_MULTIPLIER as u64, 0);
+        large.settle_rewards(&mut global_pool, 0).unwrap();
+        large.add_stake(&mut global_pool, 3 * BASE_MULTIPLIER as u64, 0);
+
+        small.settle_rewards(&mut global_pool, 10).unwrap();
+        large.settle_rewards(&mut global_pool, 10).unwrap();
+        assert_eq!(small.rewards[0], 250);
+        assert_eq!(large.rewards[0], 750);
+
+        // Unstaked user stops earning, accumulator rounds remaining share down
+        small.remove_stake(&mut global_pool, BASE_MULTIPLIER as u64).unwrap();
+        large.settle_rewards(&mut global_pool, 20).unwrap();
+        assert_eq!(large.rewards[0], 750 + 999);
+        small.settle_rewards(&mut global_pool, 20).unwrap();
+        assert_eq!(small.rewards[0], 250);
+    }
+
+    #[test]
+    fn reward_stream_stops_at_funded_amount() {
+        let weight = BASE_MULTIPLIER as u64;
+        let mut stream = RewardStream {
+            rate: 10,
+            end_time: 1_000,
+            funded: 150,
+            ..Default::default()
+        };
+        stream.update(weight, 10);
+        assert_eq!(stream.emitted, 100);
+
+        stream.update(weight, 20);
+        assert_eq!(stream.emitted, 150);
+        assert_eq!(stream.accumulated(weight), 150);
+
+        stream.update(weight, 30);
+        assert_eq!(stream.emitted, 150);
+        assert_eq!(stream.obligation(), 150);
+    }
+
+    #[test]
+    fn reward_stream_stops_at_end_time() {
+        let weight = BASE_MULTIPLIER as u64;
+        let mut stream = RewardStream {
+            rate: 10,
+            start_time: 5,
+            end_time: 15,
+            funded: u64::MAX,
+            ..Default::default()
+        };
+        stream.update(weight, 100);
+        assert_eq!(stream.emitted, 100);
+        assert_eq!(stream.last_update_time, 100);
+    }
+
+    #[test]
+    fn reward_stream_emits_nothing_without_stake() {
+        let weight = BASE_MULTIPLIER as u64;
+        let mut stream = RewardStream {
+            rate: 10,
+            end_time: 1_000,
+            funded: u64::MAX,
+            ..Default::default()
+        };
+        stream.update(0, 10);
+        assert_eq!(stream.emitted, 0);
+
+        stream.update(weight, 20);
+        assert_eq!(stream.emitted, 100);
+    }
+
+    #[test]
+    fn streak_bonus_counts_only_after_threshold() {
+        let mut global_pool = GlobalPool::default();
+        global_pool.streak_steps[0] = StreakStep { days: 10, bonus_bps: 1_000 };
+        global_pool.streak_steps[1] = StreakStep { days: 20, bonus_bps: 2_000 };
+        let start = DAY_SECONDS;
+
+        // Half of the period before first step
+        assert_eq!(
+            global_pool.streak_bonus_over(start, start, start + 20 * DAY_SECONDS),
+            500
+        );
+        // Half at each step
+        assert_eq!(
+            global_pool.streak_bonus_over(
+                start,
+                start + 10 * DAY_SECONDS,
+                start + 30 * DAY_SECONDS
+            ),
+            1_500
+        );
+        assert_eq!(
+            global_pool.streak_bonus_over(0, start, start + 30 * DAY_SECONDS),
+            0
+        );
+    }
+
+    #[test]
+    fn streak_bonus_is_capped_by_funds_left() {
+        let mut global_pool = pool_with_stream(100, 1_100);
+        global_pool.streak_steps[0] = StreakStep {
+            days: 0,
+            bonus_bps: MAX_STREAK_BONUS,
+        };
+        let start = DAY_SECONDS;
+        let mut user_pool = UserPool::default();
+        user_pool.settle_rewards(&mut global_pool, start).unwrap();
+        user_pool.add_stake(&mut global_pool, BASE_MULTIPLIER as u64, start);
+
+        user_pool.settle_rewards(&mut global_pool, start + 10).unwrap();
+        assert_eq!(user_pool.rewards[0], 1_000 + 100);
+        assert_eq!(global_pool.reward_streams[0].emitted, 1_100);
+    }
+
+    #[test]
+    fn vesting_releases_linearly() {
+        let mut vesting = Vesting::default();
+        vesting.add(1_000, 100, 0);
+
+        vesting.release(25);
+        assert_eq!(vesting.released, 250);
+        assert_eq!(vesting.locked, 750);
+
+        vesting.release(200);
+        assert_eq!(vesting.released, 1_000);
+        assert_eq!(vesting.locked, 0);
+    }
+
+    #[test]
+    fn vesting_merges_end_time_by_amount() {
+        let mut vesting = Vesting::default();
+        vesting.add(1_000, 100, 0);
+        vesting.add(1_000, 100, 50);
+
+        // 500 locked for 50 more seconds merged with 1000 locked for 100 seconds
+        assert_eq!(vesting.released, 500);
+        assert_eq!(vesting.locked, 1_500);
+        assert_eq!(vesting.end_time, 50 + (500 * 50 + 1_000 * 100) / 1_500);
+    }
+
+    #[test]
+    fn vesting_without_duration_releases_instantly() {
+        let mut vesting = Vesting::default();
+        vesting.add(1_000, 0, 10);
+        assert_eq!(vesting.released, 1_000);
+        assert_eq!(vesting.locked, 0);
+    }
+
+    #[test]
+    fn revenue_carries_until_staked_and_splits_per_nft() {
+        let mut global_pool = GlobalPool::default();
+        global_pool.distribute_revenue(100).unwrap();
+        assert_eq!(global_pool.revenue_carry, 100);
+
+        let mut one_nft = UserPool::default();
+        let mut two_nfts = UserPool::default();
+        one_nft.settle_rewards(&mut global_pool, 0).unwrap();
+        one_nft.add_stake(&mut global_pool, BASE_MULTIPLIER as u64, 0);
+        for _ in 0..2 {
+            two_nfts.settle_rewards(&mut global_pool, 0).unwrap();
+            two_nfts.add_stake(&mut global_pool, BASE_MULTIPLIER as u64, 0);
+        }
+
+        // 1000 lamports over 3 NFTs, dust carries over
+        global_pool.distribute_revenue(900).unwrap();
+        assert_eq!(global_pool.revenue_carry, 1);
+
+        one_nft.settle_rewards(&mut global_pool, 0).unwrap();
+        two_nfts.settle_rewards(&mut global_pool, 0).unwrap();
+        assert_eq!(one_nft.revenue, 333);
+        assert_eq!(two_nfts.revenue, 666);
+    }
+
+    #[test]
+    fn distribution_bitmap_tracks_claims() {
+        assert_eq!(Distribution::bitmap_size(0), 0);
+        assert_eq!(Distribution::bitmap_size(8), 1);
+        assert_eq!(Distribution::bitmap_size(9), 2);
+
+        let mut distribution = Distribution {
+            leaf_cnt: 10,
+            claimed: vec![0; Distribution::bitmap_size(10)],
+            ..Default::default()
+        };
+        assert!(!distribution.is_claimed(9));
+
+        distribution.set_claimed(9);
+        distribution.set_claimed(0);
+        assert!(distribution.is_claimed(9));
+        assert!(distribution.is_claimed(0));
+        assert!(!distribution.is_claimed(8));
+        assert!(!distribution.is_claimed(1));
+        assert_eq!(distribution.claimed, vec![0b1, 0b10]);
+    }
+
+    #[test]
+    fn token_stake_weighs_against_nfts() {
+        // 1000 tokens weigh as one default NFT
+        let mut global_pool = GlobalPool {
+            token_weight_ratio: 1_000,
+            ..Default::default()
+        };
+        let mut user_pool = UserPool::default();
+        user_pool.set_token_stake(&mut global_pool, 2_500).unwrap();
+        assert_eq!(user_pool.token_weight, 25_000);
+        assert_eq!(user_pool.stake_weight, 25_000);
+        assert_eq!(global_pool.total_stake_weight, 25_000);
+        assert_eq!(global_pool.total_staked_amount, 2_500);
+
+        // NFT weight is kept when token stake changes
+        user_pool.add_stake(&mut global_pool, BASE_MULTIPLIER as u64, 0);
+        user_pool.set_token_stake(&mut global_pool, 500).unwrap();
+        assert_eq!(user_pool.token_weight, 5_000);
+        assert_eq!(user_pool.stake_weight, 15_000);
+        assert_eq!(global_pool.total_stake_weight, 15_000);
+        assert_eq!(global_pool.total_staked_amount, 500);
+
+        // New ratio reweights stake on its next settlement
+        global_pool.token_weight_ratio = 500;
+        user_pool.settle_rewards(&mut global_pool, 0).unwrap();
+        assert_eq!(user_pool.stake_weight, 20_000);
+
+        global_pool.token_weight_ratio = 0;
+        user_pool.settle_rewards(&mut global_pool, 0).unwrap();
+        assert_eq!(user_pool.stake_weight, BASE_MULTIPLIER as u64);
+        assert_eq!(global_pool.total_stake_weight, BASE_MULTIPLIER as u64);
+    }
+
+    #[test]
+    fn token_weight_overflow_is_rejected() {
+        let global_pool = GlobalPool {
+            token_weight_ratio: 1,
+            ..Default::default()
+        };
+        assert!(global_pool.token_weight(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn unlock_waits_for_unbonding_and_vote_lock() {
+        let mut global_pool = GlobalPool {
+            unbonding_period: 100,
+            ..Default::default()
+        };
+        let mut staked_nft = StakedNft {
+            lock_end: 50,
+            ..Default::default()
+        };
+        // One-step exit is closed while unbonding period is set
+        assert!(staked_nft.check_unlock(&global_pool, 0, 1_000).is_err());
+
+        staked_nft.unlock_requested_at = 1_000;
+        assert!(staked_nft.check_unlock(&global_pool, 0, 1_099).is_err());
+        assert!(staked_nft.check_unlock(&global_pool, 0, 1_100).is_ok());
+        assert!(staked_nft.check_unlock(&global_pool, 1_200, 1_100).is_err());
+
+        global_pool.unbonding_period = 0;
+        staked_nft.unlock_requested_at = 0;
+        assert!(staked_nft.check_unlock(&global_pool, 0, 49).is_err());
+        assert!(staked_nft.check_unlock(&global_pool, 0, 50).is_ok());
+        assert!(staked_nft.check_unlock(&global_pool, 60, 50).is_err());
+    }
+
+    #[test]
+    fn remove_stake_without_stake_fails() {
+        let mut global_pool = GlobalPool::default();
+        let mut user_pool = UserPool::default();
+        assert!(user_pool
+            .remove_stake(&mut global_pool, BASE_MULTIPLIER as u64)
+            .is_err());
+    }
+
+    #[test]
+    fn revenue_overflow_is_rejected() {
+        let mut global_pool = GlobalPool {
+            revenue_carry: u64::MAX,
+            ..Default::default()
+        };
+        assert!(global_pool.distribute_revenue(1).is_err());
+        assert_eq!(global_pool.revenue_carry, u64::MAX);
+    }
+
+    #[test]
+    fn lock_tier_sets_lock_end_and_weight() {
+        let mut global_pool = GlobalPool::default();
+        global_pool.lock_tiers[1] = LockTier {
+            duration_days: 30,
+            multiplier_bps: 12_500,
+        };
+        assert!(global_pool.lock_tier(0).is_err());
+        assert!(global_pool.lock_tier(MAX_LOCK_TIERS as u8).is_err());
+
+        let mut staked_nft = StakedNft::default();
+        staked_nft.stake(
+            Pubkey::default(),
+            Pubkey::default(),
+            global_pool.lock_tier(1).unwrap(),
+            2,
+            Custody::TokenMetadataLock,
+            1_000,
+        );
+        assert_eq!(staked_nft.lock_end, 1_000 + 30 * DAY_SECONDS);
+        assert_eq!(staked_nft.weight, 2 * 12_500);
+
+        assert!(staked_nft
+            .check_unlock(&global_pool, 0, staked_nft.lock_end - 1)
+            .is_err());
+        assert!(staked_nft
+            .check_unlock(&global_pool, 0, staked_nft.lock_end)
+            .is_ok());
+    }
+
+    #[test]
+    fn rarity_weight_is_proved_against_root() {
+        let mint = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut global_pool = GlobalPool::default();
+        assert_eq!(global_pool.rarity_weight(&mint, 7, &[]).unwrap(), DEFAULT_NFT_WEIGHT);
+
+        // Leaf is keccak of mint and little endian weight, pairs are hashed sorted
+        let leaf = keccak::hashv(&[mint.as_ref(), &7u16.to_le_bytes()]).0;
+        let sibling = keccak::hashv(&[other.as_ref(), &3u16.to_le_bytes()]).0;
+        global_pool.weight_root = if leaf <= sibling {
+            keccak::hashv(&[&leaf, &sibling]).0
+        } else {
+            keccak::hashv(&[&sibling, &leaf]).0
+        };
+
+        assert_eq!(global_pool.rarity_weight(&mint, 7, &[sibling]).unwrap(), 7);
+        assert!(global_pool.rarity_weight(&mint, 8, &[sibling]).is_err());
+        assert!(global_pool.rarity_weight(&other, 7, &[leaf]).is_err());
+        assert_eq!(global_pool.rarity_weight(&other, 3, &[leaf]).unwrap(), 3);
+    }
 }
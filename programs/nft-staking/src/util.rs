@@ -1,6 +1,121 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{program::invoke, system_instruction::transfer};
-use std::cmp::Ordering;
+use anchor_lang::solana_program::{
+    instruction::{get_stack_height, Instruction, TRANSACTION_LEVEL_STACK_HEIGHT},
+    keccak,
+    program::{invoke, invoke_signed},
+    system_instruction::transfer,
+    sysvar::instructions::get_instruction_relative,
+};
+use mpl_token_metadata::instruction::{
+    builders::{DelegateBuilder, LockBuilder, RevokeBuilder, TransferBuilder, UnlockBuilder},
+    DelegateArgs, InstructionBuilder, LockArgs, RevokeArgs, TransferArgs, UnlockArgs,
+};
+use std::{cmp::Ordering, str::FromStr};
+
+/**
+ * Verify merkle proof of leaf, pairs are hashed in sorted order
+ */
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed_hash = leaf;
+    for proof_element in proof.iter() {
+        computed_hash = if computed_hash <= *proof_element {
+            keccak::hashv(&[&computed_hash, proof_element]).0
+        } else {
+            keccak::hashv(&[proof_element, &computed_hash]).0
+        };
+    }
+    computed_hash == root
+}
+
+/**
+ * Program that invoked this instruction through CPI
+ * only a top level program calling directly is accepted, it is the one program we can identify
+ */
+pub fn direct_caller(sysvar_instructions: &AccountInfo) -> Result<Pubkey> {
+    require!(
+        get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT + 1,
+        crate::StakingError::UnauthorizedCaller
+    );
+    let caller = get_instruction_relative(0, sysvar_instructions)?.program_id;
+    require!(caller != crate::ID, crate::StakingError::UnauthorizedCaller);
+    Ok(caller)
+}
+
+/**
+ * Transfer tokens from a vault owned by global pool
+ */
+pub fn transfer_from_vault<'info>(
+    vault: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    global_pool: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    global_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[crate::GLOBAL_AUTHORITY_SEED.as_bytes(), &[global_bump]];
+    let signer = &[&seeds[..]];
+
+    anchor_spl::token::transfer(
+        CpiContext::new_with_signer(
+            token_program,
+            anchor_spl::token::Transfer {
+                from: vault,
+                to,
+                authority: global_pool,
+            },
+            signer,
+        ),
+        amount,
+    )
+}
+
+/**
+ * Transfer lamports fee from payer to treasury, skipped when fee is zero
+ */
+pub fn transfer_fee<'info>(
+    payer: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    fee: u64,
+) -> Result<()> {
+    if fee > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program,
+                anchor_lang::system_program::Transfer {
+                    from: payer,
+                    to: treasury,
+                },
+            ),
+            fee,
+        )?;
+    }
+    Ok(())
+}
+
+/**
+ * Grow account written with an older layout of the same type, new fields read as zero
+ */
+pub fn grow_account<'info>(
+    account_info: &AccountInfo<'info>,
+    discriminator: [u8; 8],
+    new_space: usize,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    require!(
+        *account_info.owner == crate::ID,
+        ErrorCode::AccountOwnedByWrongProgram
+    );
+    require!(
+        account_info.try_borrow_data()?.get(..8) == Some(&discriminator[..]),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    if account_info.data_len() < new_space {
+        resize_account(account_info.clone(), new_space, payer, system_program)?;
+    }
+    Ok(())
+}
 
 pub fn resize_account<'info>(
     account_info: AccountInfo<'info>,
@@ -33,3 +148,712 @@ pub fn resize_account<'info>(
     account_info.realloc(new_space, false)?;
     Ok(())
 }
+
+/**
+ * Accounts used by token metadata staking delegate CPIs
+ * delegate is the user pool PDA that locks the pNFT
+ */
+pub struct PnftAccounts<'info> {
+    pub delegate: AccountInfo<'info>,
+    pub token_owner: AccountInfo<'info>,
+    pub token_account: AccountInfo<'info>,
+    pub token_mint: AccountInfo<'info>,
+    pub token_mint_edition: AccountInfo<'info>,
+    pub token_mint_record: AccountInfo<'info>,
+    pub mint_metadata: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub auth_rules_program: AccountInfo<'info>,
+    pub auth_rules: AccountInfo<'info>,
+}
+
+/**
+ * Token owner approves delegate as staking delegate, then delegate locks the pNFT
+ * token owner must sign the transaction
+ */
+pub fn delegate_and_lock_pnft(
+    accounts: &PnftAccounts,
+    delegate_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let delegate_ix = DelegateBuilder::new()
+        .delegate(accounts.delegate.key())
+        .metadata(accounts.mint_metadata.key())
+        .master_edition(accounts.token_mint_edition.key())
+        .token_record(accounts.token_mint_record.key())
+        .mint(accounts.token_mint.key())
+        .token(accounts.token_account.key())
+        .authority(accounts.token_owner.key())
+        .payer(accounts.payer.key())
+        .system_program(accounts.system_program.key())
+        .sysvar_instructions(accounts.sysvar_instructions.key())
+        .spl_token_program(accounts.token_program.key())
+        .authorization_rules_program(accounts.auth_rules_program.key())
+        .authorization_rules(accounts.auth_rules.key())
+        .build(DelegateArgs::StakingV1 {
+            amount: 1,
+            authorization_data: None,
+        })
+        .unwrap()
+        .instruction();
+
+    invoke(
+        &delegate_ix,
+        &[
+            accounts.delegate.clone(),
+            accounts.mint_metadata.clone(),
+            accounts.token_mint_edition.clone(),
+            accounts.token_mint_record.clone(),
+            accounts.token_mint.clone(),
+            accounts.token_account.clone(),
+            accounts.token_owner.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.sysvar_instructions.clone(),
+            accounts.token_program.clone(),
+            accounts.auth_rules_program.clone(),
+            accounts.auth_rules.clone(),
+        ],
+    )?;
+
+    let lock_ix = LockBuilder::new()
+        .authority(accounts.delegate.key())
+        .token_owner(accounts.token_owner.key())
+        .token(accounts.token_account.key())
+        .mint(accounts.token_mint.key())
+        .metadata(accounts.mint_metadata.key())
+        .edition(accounts.token_mint_edition.key())
+        .token_record(accounts.token_mint_record.key())
+        .payer(accounts.payer.key())
+        .system_program(accounts.system_program.key())
+        .sysvar_instructions(accounts.sysvar_instructions.key())
+        .spl_token_program(accounts.token_program.key())
+        .authorization_rules_program(accounts.auth_rules_program.key())
+        .authorization_rules(accounts.auth_rules.key())
+        .build(LockArgs::V1 { authorization_data: None })
+        .unwrap()
+        .instruction();
+
+    invoke_signed(
+        &lock_ix,
+        &[
+            accounts.delegate.clone(),
+            accounts.token_owner.clone(),
+            accounts.token_account.clone(),
+            accounts.token_mint.clone(),
+            accounts.mint_metadata.clone(),
+            accounts.token_mint_edition.clone(),
+            accounts.token_mint_record.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.sysvar_instructions.clone(),
+            accounts.token_program.clone(),
+            accounts.auth_rules_program.clone(),
+            accounts.auth_rules.clone(),
+        ],
+        delegate_seeds,
+    )?;
+
+    Ok(())
+}
+
+/**
+ * Delegate unlocks the pNFT, then revoke authority removes the staking delegate
+ * revoke authority is either the token owner or the delegate revoking itself
+ */
+pub fn unlock_and_revoke_pnft<'info>(
+    accounts: &PnftAccounts<'info>,
+    revoke_authority: &AccountInfo<'info>,
+    delegate_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let unlock_ix = UnlockBuilder::new()
+        .authority(accounts.delegate.key())
+        .token_owner(accounts.token_owner.key())
+        .token(accounts.token_account.key())
+        .mint(accounts.token_mint.key())
+        .metadata(accounts.mint_metadata.key())
+        .edition(accounts.token_mint_edition.key())
+        .token_record(accounts.token_mint_record.key())
+        .payer(accounts.payer.key())
+        .system_program(accounts.system_program.key())
+        .sysvar_instructions(accounts.sysvar_instructions.key())
+        .spl_token_program(accounts.token_program.key())
+        .authorization_rules_program(accounts.auth_rules_program.key())
+        .authorization_rules(accounts.auth_rules.key())
+        .build(UnlockArgs::V1 { authorization_data: None })
+        .unwrap()
+        .instruction();
+
+    invoke_signed(
+        &unlock_ix,
+        &[
+            accounts.delegate.clone(),
+            accounts.token_owner.clone(),
+            accounts.token_account.clone(),
+            accounts.token_mint.clone(),
+            accounts.mint_metadata.clone(),
+            accounts.token_mint_edition.clone(),
+            accounts.token_mint_record.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.sysvar_instructions.clone(),
+            accounts.token_program.clone(),
+            accounts.auth_rules_program.clone(),
+            accounts.auth_rules.clone(),
+        ],
+        delegate_seeds,
+    )?;
+
+    let revoke_ix = RevokeBuilder::new()
+        .delegate(accounts.delegate.key())
+        .metadata(accounts.mint_metadata.key())
+        .master_edition(accounts.token_mint_edition.key())
+        .token_record(accounts.token_mint_record.key())
+        .mint(accounts.token_mint.key())
+        .token(accounts.token_account.key())
+        .authority(revoke_authority.key())
+        .payer(accounts.payer.key())
+        .system_program(accounts.system_program.key())
+        .sysvar_instructions(accounts.sysvar_instructions.key())
+        .spl_token_program(accounts.token_program.key())
+        .authorization_rules_program(accounts.auth_rules_program.key())
+        .authorization_rules(accounts.auth_rules.key())
+        .build(RevokeArgs::StakingV1)
+        .unwrap()
+        .instruction();
+
+    invoke_signed(
+        &revoke_ix,
+        &[
+            accounts.delegate.clone(),
+            accounts.mint_metadata.clone(),
+            accounts.token_mint_edition.clone(),
+            accounts.token_mint_record.clone(),
+            accounts.token_mint.clone(),
+            accounts.token_account.clone(),
+            revoke_authority.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.sysvar_instructions.clone(),
+            accounts.token_program.clone(),
+            accounts.auth_rules_program.clone(),
+            accounts.auth_rules.clone(),
+        ],
+        delegate_seeds,
+    )?;
+
+    Ok(())
+}
+
+/**
+ * Transfer pNFT through token metadata so token records follow the token
+ * token owner is the authority, signing directly or through seeds
+ */
+pub fn transfer_pnft<'info>(
+    accounts: &PnftAccounts<'info>,
+    destination: &AccountInfo<'info>,
+    destination_owner: &AccountInfo<'info>,
+    destination_record: &AccountInfo<'info>,
+    ata_program: &AccountInfo<'info>,
+    owner_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let transfer_ix = TransferBuilder::new()
+        .token(accounts.token_account.key())
+        .token_owner(accounts.token_owner.key())
+        .destination(destination.key())
+        .destination_owner(destination_owner.key())
+        .mint(accounts.token_mint.key())
+        .metadata(accounts.mint_metadata.key())
+        .edition(accounts.token_mint_edition.key())
+        .owner_token_record(accounts.token_mint_record.key())
+        .destination_token_record(destination_record.key())
+        .authority(accounts.token_owner.key())
+        .payer(accounts.payer.key())
+        .system_program(accounts.system_program.key())
+        .sysvar_instructions(accounts.sysvar_instructions.key())
+        .spl_token_program(accounts.token_program.key())
+        .spl_ata_program(ata_program.key())
+        .authorization_rules_program(accounts.auth_rules_program.key())
+        .authorization_rules(accounts.auth_rules.key())
+        .build(TransferArgs::V1 {
+            amount: 1,
+            authorization_data: None,
+        })
+        .unwrap()
+        .instruction();
+
+    invoke_signed(
+        &transfer_ix,
+        &[
+            accounts.token_account.clone(),
+            accounts.token_owner.clone(),
+            destination.clone(),
+            destination_owner.clone(),
+            accounts.token_mint.clone(),
+            accounts.mint_metadata.clone(),
+            accounts.token_mint_edition.clone(),
+            accounts.token_mint_record.clone(),
+            destination_record.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.sysvar_instructions.clone(),
+            accounts.token_program.clone(),
+            ata_program.clone(),
+            accounts.auth_rules_program.clone(),
+            accounts.auth_rules.clone(),
+        ],
+        owner_seeds,
+    )?;
+
+    Ok(())
+}
+
+/**
+ * Find extension data of a Token-2022 mint by extension type
+ * parsed by hand so extensions unknown to the token crate don't fail the lookup
+ */
+pub fn find_mint_extension(mint_data: &[u8], extension_type: u16) -> Option<&[u8]> {
+    let mut offset = crate::TOKEN_2022_EXTENSIONS_OFFSET;
+    while offset + 4 <= mint_data.len() {
+        let entry_type = u16::from_le_bytes([mint_data[offset], mint_data[offset + 1]]);
+        let length = u16::from_le_bytes([mint_data[offset + 2], mint_data[offset + 3]]) as usize;
+        let start = offset + 4;
+        // Uninitialized entry marks the end of extensions
+        if entry_type == 0 || start + length > mint_data.len() {
+            return None;
+        }
+        if entry_type == extension_type {
+            return Some(&mint_data[start..start + length]);
+        }
+        offset = start + length;
+    }
+    None
+}
+
+/**
+ * Check Token-2022 NFT belongs to collection without Metaplex metadata
+ * its group member extension must point to collection group mint,
+ * group authority co-signs membership so it can't be forged by the minter
+ */
+pub fn verify_token_2022_collection(token_mint: &AccountInfo) -> Result<()> {
+    require!(
+        *token_mint.owner == anchor_spl::token_2022::ID,
+        crate::StakingError::InvalidMetadata
+    );
+    let group = Pubkey::from_str(crate::TOKEN_2022_COLLECTION_GROUP).unwrap();
+    let mint_data = token_mint.try_borrow_data()?;
+
+    // Group member: mint (32), group (32), member number (8)
+    let member = find_mint_extension(&mint_data, crate::EXTENSION_TOKEN_GROUP_MEMBER)
+        .ok_or(crate::StakingError::InvalidCollection)?;
+    require!(
+        member.len() >= 64
+            && member[..32] == token_mint.key().to_bytes()
+            && member[32..64] == group.to_bytes(),
+        crate::StakingError::InvalidCollection
+    );
+
+    Ok(())
+}
+
+/**
+ * Move NFT between owner's token account and escrow with transfer_checked
+ */
+pub fn transfer_nft<'info>(
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    anchor_spl::token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program,
+            anchor_spl::token_interface::TransferChecked {
+                from,
+                mint,
+                to,
+                authority,
+            },
+            signer_seeds,
+        ),
+        1,
+        0,
+    )
+}
+
+/**
+ * Return escrowed NFT to owner's token account and close escrow, rent goes to owner
+ * user pool is the escrow authority
+ */
+pub fn release_escrow<'info>(
+    escrow: AccountInfo<'info>,
+    accounts: &PnftAccounts<'info>,
+    delegate_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    transfer_nft(
+        escrow.clone(),
+        accounts.token_account.clone(),
+        accounts.token_mint.clone(),
+        accounts.delegate.clone(),
+        accounts.token_program.clone(),
+        delegate_seeds,
+    )?;
+
+    anchor_spl::token_interface::close_account(CpiContext::new_with_signer(
+        accounts.token_program.clone(),
+        anchor_spl::token_interface::CloseAccount {
+            account: escrow,
+            destination: accounts.token_owner.clone(),
+            authority: accounts.delegate.clone(),
+        },
+        delegate_seeds,
+    ))
+}
+
+/**
+ * Give staked NFT back to owner according to how it is held
+ */
+pub fn release_nft<'info>(
+    custody: crate::Custody,
+    accounts: &PnftAccounts<'info>,
+    revoke_authority: &AccountInfo<'info>,
+    escrow: Option<AccountInfo<'info>>,
+    delegate_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    match custody {
+        crate::Custody::TokenMetadataLock => {
+            unlock_and_revoke_pnft(accounts, revoke_authority, delegate_seeds)
+        }
+        crate::Custody::Escrow => {
+            let escrow = escrow.ok_or(crate::StakingError::EscrowRequired)?;
+            release_escrow(escrow, accounts, delegate_seeds)
+        }
+        crate::Custody::InPlace => Ok(()),
+        // Core assets, compressed NFTs and receipt positions are released by their own paths
+        crate::Custody::CoreFreeze
+        | crate::Custody::CompressedEscrow
+        | crate::Custody::ReceiptEscrow => {
+            err!(crate::StakingError::InvalidCustody)
+        }
+    }
+}
+
+//  Metaplex Core account layouts, only the parts read by staking
+
+#[derive(AnchorDeserialize)]
+pub enum CoreUpdateAuthority {
+    None,
+    Address(Pubkey),
+    Collection(Pubkey),
+}
+
+#[derive(AnchorDeserialize, PartialEq)]
+pub enum CoreAuthority {
+    None,
+    Owner,
+    UpdateAuthority,
+    Address { address: Pubkey },
+}
+
+#[derive(AnchorDeserialize)]
+struct CoreAssetBase {
+    key: u8,
+    owner: Pubkey,
+    update_authority: CoreUpdateAuthority,
+    _name: String,
+    _uri: String,
+    _seq: Option<u64>,
+}
+
+#[derive(AnchorDeserialize)]
+struct CorePluginHeader {
+    _key: u8,
+    plugin_registry_offset: u64,
+}
+
+#[derive(AnchorDeserialize)]
+struct CoreRegistryRecord {
+    plugin_type: u8,
+    authority: CoreAuthority,
+    offset: u64,
+}
+
+#[derive(AnchorDeserialize)]
+struct CorePluginRegistry {
+    _key: u8,
+    registry: Vec<CoreRegistryRecord>,
+}
+
+/**
+ * Freeze delegate plugin of a Core asset
+ */
+pub struct CoreFreezeDelegate {
+    pub authority: CoreAuthority,
+    pub frozen: bool,
+}
+
+/**
+ * Core asset fields checked when staking
+ */
+pub struct CoreAsset {
+    pub owner: Pubkey,
+    pub update_authority: CoreUpdateAuthority,
+    pub freeze_delegate: Option<CoreFreezeDelegate>,
+}
+
+impl CoreAsset {
+    pub fn collection(&self) -> Option<Pubkey> {
+        match self.update_authority {
+            CoreUpdateAuthority::Collection(collection) => Some(collection),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * Parse Core asset and find its freeze delegate plugin in the plugin registry
+ */
+pub fn parse_core_asset(data: &[u8]) -> Result<CoreAsset> {
+    let mut cursor = data;
+    let base = CoreAssetBase::deserialize(&mut cursor)
+        .map_err(|_| error!(crate::StakingError::InvalidMetadata))?;
+    require!(base.key == crate::CORE_ASSET_KEY, crate::StakingError::InvalidMetadata);
+
+    let mut freeze_delegate = None;
+    // Plugin header follows the base asset when asset has plugins
+    if !cursor.is_empty() {
+        let header = CorePluginHeader::deserialize(&mut cursor)
+            .map_err(|_| error!(crate::StakingError::InvalidMetadata))?;
+        let registry = data
+            .get(header.plugin_registry_offset as usize..)
+            .and_then(|mut registry| CorePluginRegistry::deserialize(&mut registry).ok())
+            .ok_or(crate::StakingError::InvalidMetadata)?;
+
+        for record in registry.registry {
+            if record.plugin_type == crate::CORE_FREEZE_DELEGATE_PLUGIN {
+                // Plugin data is the plugin tag and frozen flag
+                let frozen = data
+                    .get(record.offset as usize + 1)
+                    .ok_or(crate::StakingError::InvalidMetadata)?;
+                freeze_delegate = Some(CoreFreezeDelegate {
+                    authority: record.authority,
+                    frozen: *frozen != 0,
+                });
+            }
+        }
+    }
+
+    Ok(CoreAsset {
+        owner: base.owner,
+        update_authority: base.update_authority,
+        freeze_delegate,
+    })
+}
+
+/**
+ * Accounts used by Metaplex Core plugin CPIs
+ */
+pub struct CoreAccounts<'info> {
+    pub asset: AccountInfo<'info>,
+    pub collection: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub core_program: AccountInfo<'info>,
+}
+
+fn invoke_core<'info>(
+    accounts: &CoreAccounts<'info>,
+    authority: &AccountInfo<'info>,
+    data: Vec<u8>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_signed(
+        &Instruction {
+            program_id: accounts.core_program.key(),
+            accounts: vec![
+                AccountMeta::new(accounts.asset.key(), false),
+                AccountMeta::new(accounts.collection.key(), false),
+                AccountMeta::new(accounts.payer.key(), true),
+                AccountMeta::new_readonly(authority.key(), true),
+                AccountMeta::new_readonly(accounts.system_program.key(), false),
+                // No log wrapper
+                AccountMeta::new_readonly(accounts.core_program.key(), false),
+            ],
+            data,
+        },
+        &[
+            accounts.asset.clone(),
+            accounts.collection.clone(),
+            accounts.payer.clone(),
+            authority.clone(),
+            accounts.system_program.clone(),
+            accounts.core_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/**
+ * Make delegate the freeze authority of Core asset and freeze it
+ * owner must sign the transaction
+ */
+pub fn freeze_core_asset<'info>(
+    accounts: &CoreAccounts<'info>,
+    asset: &CoreAsset,
+    owner: &AccountInfo<'info>,
+    delegate: &AccountInfo<'info>,
+    delegate_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let delegate_authority = CoreAuthority::Address {
+        address: delegate.key(),
+    };
+
+    match &asset.freeze_delegate {
+        None => {
+            // AddPluginV1 { plugin: FreezeDelegate { frozen: true }, init_authority: Some(delegate) }
+            let mut data = vec![
+                crate::CORE_ADD_PLUGIN_IX,
+                crate::CORE_FREEZE_DELEGATE_PLUGIN,
+                1,
+                1,
+                crate::CORE_AUTHORITY_ADDRESS,
+            ];
+            data.extend_from_slice(delegate.key.as_ref());
+            return invoke_core(accounts, owner, data, &[]);
+        }
+        Some(plugin) => {
+            require!(!plugin.frozen, crate::StakingError::AssetFrozen);
+            if plugin.authority != delegate_authority {
+                // ApprovePluginAuthorityV1 { plugin_type: FreezeDelegate, new_authority: delegate }
+                let mut data = vec![
+                    crate::CORE_APPROVE_PLUGIN_AUTHORITY_IX,
+                    crate::CORE_FREEZE_DELEGATE_PLUGIN,
+                    crate::CORE_AUTHORITY_ADDRESS,
+                ];
+                data.extend_from_slice(delegate.key.as_ref());
+                invoke_core(accounts, owner, data, &[])?;
+            }
+        }
+    }
+
+    // UpdatePluginV1 { plugin: FreezeDelegate { frozen: true } }
+    invoke_core(
+        accounts,
+        delegate,
+        vec![crate::CORE_UPDATE_PLUGIN_IX, crate::CORE_FREEZE_DELEGATE_PLUGIN, 1],
+        delegate_seeds,
+    )
+}
+
+/**
+ * Delegate thaws Core asset and revokes itself, freeze authority goes back to owner
+ */
+pub fn thaw_core_asset<'info>(
+    accounts: &CoreAccounts<'info>,
+    delegate: &AccountInfo<'info>,
+    delegate_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    // UpdatePluginV1 { plugin: FreezeDelegate { frozen: false } }
+    invoke_core(
+        accounts,
+        delegate,
+        vec![crate::CORE_UPDATE_PLUGIN_IX, crate::CORE_FREEZE_DELEGATE_PLUGIN, 0],
+        delegate_seeds,
+    )?;
+
+    // RevokePluginAuthorityV1 { plugin_type: FreezeDelegate }
+    invoke_core(
+        accounts,
+        delegate,
+        vec![crate::CORE_REVOKE_PLUGIN_AUTHORITY_IX, crate::CORE_FREEZE_DELEGATE_PLUGIN],
+        delegate_seeds,
+    )
+}
+
+/**
+ * Accounts used by bubblegum transfer CPI, proof nodes come from remaining accounts
+ */
+pub struct CnftAccounts<'info> {
+    pub tree_authority: AccountInfo<'info>,
+    pub merkle_tree: AccountInfo<'info>,
+    pub log_wrapper: AccountInfo<'info>,
+    pub compression_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub bubblegum_program: AccountInfo<'info>,
+}
+
+/**
+ * Asset id of a compressed NFT leaf
+ */
+pub fn get_asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"asset", merkle_tree.as_ref(), &nonce.to_le_bytes()],
+        &Pubkey::from_str(crate::BUBBLEGUM_PROGRAM_ADDRESS).unwrap(),
+    )
+    .0
+}
+
+/**
+ * Transfer compressed NFT leaf, leaf owner signs directly or through seeds
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_cnft<'info>(
+    accounts: &CnftAccounts<'info>,
+    leaf_owner: &AccountInfo<'info>,
+    leaf_delegate: &AccountInfo<'info>,
+    new_leaf_owner: &AccountInfo<'info>,
+    proof: &[AccountInfo<'info>],
+    root: [u8; 32],
+    metadata: &crate::MetadataArgs,
+    nonce: u64,
+    index: u32,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = crate::BUBBLEGUM_TRANSFER_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&root);
+    data.extend_from_slice(&metadata.data_hash()?);
+    data.extend_from_slice(&metadata.creator_hash());
+    data.extend_from_slice(&nonce.to_le_bytes());
+    data.extend_from_slice(&index.to_le_bytes());
+
+    let mut account_metas = vec![
+        AccountMeta::new_readonly(accounts.tree_authority.key(), false),
+        AccountMeta::new_readonly(leaf_owner.key(), true),
+        AccountMeta::new_readonly(leaf_delegate.key(), false),
+        AccountMeta::new_readonly(new_leaf_owner.key(), false),
+        AccountMeta::new(accounts.merkle_tree.key(), false),
+        AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+        AccountMeta::new_readonly(accounts.compression_program.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false),
+    ];
+    let mut account_infos = vec![
+        accounts.tree_authority.clone(),
+        leaf_owner.clone(),
+        leaf_delegate.clone(),
+        new_leaf_owner.clone(),
+        accounts.merkle_tree.clone(),
+        accounts.log_wrapper.clone(),
+        accounts.compression_program.clone(),
+        accounts.system_program.clone(),
+    ];
+    for node in proof {
+        account_metas.push(AccountMeta::new_readonly(node.key(), false));
+        account_infos.push(node.clone());
+    }
+    account_infos.push(accounts.bubblegum_program.clone());
+
+    invoke_signed(
+        &Instruction {
+            program_id: accounts.bubblegum_program.key(),
+            accounts: account_metas,
+            data,
+        },
+        &account_infos,
+        signer_seeds,
+    )?;
+    Ok(())
+}
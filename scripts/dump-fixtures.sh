@@ -0,0 +1,19 @@
+#!/bin/sh
+# Dump programs the local validator loads from tests/fixtures, skips the ones already dumped
+set -e
+cd "$(dirname "$0")/.."
+mkdir -p tests/fixtures
+
+dump() {
+    if [ ! -f "tests/fixtures/$2.so" ]; then
+        solana program dump -u m "$1" "tests/fixtures/$2.so"
+    fi
+}
+
+dump metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s mpl_token_metadata
+dump BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY bubblegum
+dump cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK spl_account_compression
+dump noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV spl_noop
+dump CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d mpl_core
+# Token-2022 bundled with older validators has no token group extensions
+dump TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb spl_token_2022
@@ -0,0 +1,85 @@
+import { ComputeBudgetProgram, Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { TokenState } from "@metaplex-foundation/mpl-token-metadata";
+import { assert } from "chai";
+
+import {
+  addBlocklist,
+  blocklistOf,
+  connection,
+  ensureInitialized,
+  expectError,
+  lockPnft,
+  mintPnft,
+  newUser,
+  pnftAccounts,
+  program,
+  removeBlocklist,
+  stakedNftOf,
+  tokenRecordOf,
+  userPoolOf,
+} from "./helpers";
+
+describe("blocklist", () => {
+  let user: Keypair;
+  let mint: PublicKey;
+
+  const forceUnlockPnft = async () =>
+    program.methods
+      .forceUnlockPnft()
+      .accounts({
+        ...(await pnftAccounts(user.publicKey, mint)),
+        mintBlocklist: blocklistOf(mint),
+        owner: user.publicKey,
+      })
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .rpc();
+
+  before(async () => {
+    await ensureInitialized();
+    user = await newUser();
+    mint = await mintPnft(user.publicKey);
+  });
+
+  it("rejects lock from blocked wallet", async () => {
+    await addBlocklist(user.publicKey);
+    await expectError(lockPnft(user, mint), "WalletBlocked");
+
+    await removeBlocklist(user.publicKey);
+    assert.isNull(await connection.getAccountInfo(blocklistOf(user.publicKey)));
+  });
+
+  it("rejects lock of blocked mint", async () => {
+    await addBlocklist(mint);
+    await expectError(lockPnft(user, mint), "MintBlocked");
+    await removeBlocklist(mint);
+  });
+
+  it("force unlocks only blocked mints", async () => {
+    await lockPnft(user, mint);
+    let userInfo = await program.account.userPool.fetch(userPoolOf(user.publicKey));
+    assert.equal(userInfo.stakeCnt, 1);
+
+    await expectError(forceUnlockPnft(), "AccountNotInitialized");
+
+    await addBlocklist(mint);
+    await forceUnlockPnft();
+
+    assert.isNull(await connection.getAccountInfo(stakedNftOf(mint)));
+    userInfo = await program.account.userPool.fetch(userPoolOf(user.publicKey));
+    assert.equal(userInfo.stakeCnt, 0);
+    assert.equal(userInfo.stakeWeight.toNumber(), 0);
+
+    // NFT is back with owner, unlocked and without delegate
+    const token = await getAccount(
+      connection,
+      getAssociatedTokenAddressSync(mint, user.publicKey)
+    );
+    assert.equal(token.amount, BigInt(1));
+    const record = await tokenRecordOf(user.publicKey, mint);
+    assert.equal(record.state, TokenState.Unlocked);
+    assert.isNull(record.delegate);
+
+    await removeBlocklist(mint);
+  });
+});
@@ -0,0 +1,492 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import {
+  createCreateMasterEditionV3Instruction,
+  createCreateMetadataAccountV3Instruction,
+} from "@metaplex-foundation/mpl-token-metadata";
+import {
+  computeCompressedNFTHash,
+  createCreateTreeInstruction,
+  createMintToCollectionV1Instruction,
+  createMintV1Instruction,
+  getLeafAssetId,
+  MetadataArgs,
+  PROGRAM_ID as BUBBLEGUM_PROGRAM_ID,
+  TokenProgramVersion,
+  TokenStandard,
+} from "@metaplex-foundation/mpl-bubblegum";
+import {
+  ConcurrentMerkleTreeAccount,
+  createAllocTreeIx,
+  MerkleTree,
+  SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+  SPL_NOOP_PROGRAM_ID,
+} from "@solana/spl-account-compression";
+import { assert } from "chai";
+import * as fs from "fs";
+
+import { NftStaking } from "../target/types/nft_staking";
+import { METAPLEX, getMasterEdition, getMetadata } from "../lib/util";
+import {
+  BLOCKLIST_SEED,
+  GLOBAL_AUTHORITY_SEED,
+  REVENUE_SEED,
+  STAKE_CHECKPOINT_SEED,
+  STAKED_NFT_SEED,
+  TREASURY_SEED,
+  USER_POOL_SEED,
+} from "../lib/constant";
+import { addBlocklist, removeBlocklist } from "./helpers";
+
+const MAX_DEPTH = 3;
+const MAX_BUFFER_SIZE = 8;
+
+describe("cnft staking", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.NftStaking as Program<NftStaking>;
+  const connection = provider.connection;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  const user = payer.publicKey;
+
+  // Collection the program is built to accept with localnet feature
+  const collectionMint = Keypair.fromSecretKey(
+    Uint8Array.from(
+      JSON.parse(fs.readFileSync("tests/fixtures/cnft-collection.json", "utf-8"))
+    )
+  );
+  const merkleTree = Keypair.generate();
+
+  const [globalPool] = PublicKey.findProgramAddressSync(
+    [Buffer.from(GLOBAL_AUTHORITY_SEED)],
+    program.programId
+  );
+  const [treasury] = PublicKey.findProgramAddressSync(
+    [Buffer.from(TREASURY_SEED)],
+    program.programId
+  );
+  const [revenueVault] = PublicKey.findProgramAddressSync(
+    [Buffer.from(REVENUE_SEED)],
+    program.programId
+  );
+  const [userPool] = PublicKey.findProgramAddressSync(
+    [user.toBuffer(), Buffer.from(USER_POOL_SEED)],
+    program.programId
+  );
+  const [treeAuthority] = PublicKey.findProgramAddressSync(
+    [merkleTree.publicKey.toBuffer()],
+    BUBBLEGUM_PROGRAM_ID
+  );
+
+  const metadataArgs = (verified: boolean): MetadataArgs => ({
+    name: "Staked cNFT",
+    symbol: "SCN",
+    uri: "https://example.com/cnft.json",
+    sellerFeeBasisPoints: 0,
+    primarySaleHappened: false,
+    isMutable: false,
+    editionNonce: null,
+    tokenStandard: TokenStandard.NonFungible,
+    collection: { verified, key: collectionMint.publicKey },
+    uses: null,
+    tokenProgramVersion: TokenProgramVersion.Original,
+    creators: [],
+  });
+
+  // Same metadata in the shape of the staking program IDL
+  const programMetadata = (verified: boolean) => ({
+    name: "Staked cNFT",
+    symbol: "SCN",
+    uri: "https://example.com/cnft.json",
+    sellerFeeBasisPoints: 0,
+    primarySaleHappened: false,
+    isMutable: false,
+    editionNonce: null,
+    tokenStandard: { nonFungible: {} },
+    collection: { verified, key: collectionMint.publicKey },
+    uses: null,
+    tokenProgramVersion: { original: {} },
+    creators: [],
+  });
+
+  // Leaves minted so far, the proof of a leaf is built from all of them
+  const leaves: Buffer[] = [];
+
+  const getCheckpoint = async () => {
+    const globalInfo = await program.account.globalPool.fetch(globalPool);
+    const epoch = Buffer.alloc(4);
+    epoch.writeUInt32LE(globalInfo.epoch as number);
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CHECKPOINT_SEED), user.toBuffer(), epoch],
+      program.programId
+    )[0];
+  };
+
+  const getProof = async (index: number) => {
+    const tree = await ConcurrentMerkleTreeAccount.fromAccountAddress(
+      connection,
+      merkleTree.publicKey
+    );
+    const proof = MerkleTree.sparseMerkleTreeFromLeaves(leaves, MAX_DEPTH).getProof(index);
+    return {
+      root: Array.from(tree.getCurrentRoot()),
+      proofAccounts: proof.proof.map((node) => ({
+        pubkey: new PublicKey(node),
+        isSigner: false,
+        isWritable: false,
+      })),
+    };
+  };
+
+  const stakeAccounts = async (assetId: PublicKey) => {
+    const [stakedNft] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKED_NFT_SEED), assetId.toBuffer()],
+      program.programId
+    );
+    return {
+      admin: user,
+      globalPool,
+      signer: user,
+      userPool,
+      treeAuthority,
+      merkleTree: merkleTree.publicKey,
+      stakedNft,
+      stakeCheckpoint: await getCheckpoint(),
+      treasury,
+      logWrapper: SPL_NOOP_PROGRAM_ID,
+      compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+      bubblegumProgram: BUBBLEGUM_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+  };
+
+  const lockCnft = async (index: number, verified: boolean) => {
+    const nonce = new anchor.BN(index);
+    const assetId = await getLeafAssetId(merkleTree.publicKey, nonce);
+    const { root, proofAccounts } = await getProof(index);
+    const [walletBlocklist] = PublicKey.findProgramAddressSync(
+      [Buffer.from(BLOCKLIST_SEED), user.toBuffer()],
+      program.programId
+    );
+    const [mintBlocklist] = PublicKey.findProgramAddressSync(
+      [Buffer.from(BLOCKLIST_SEED), assetId.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .lockCnft(assetId, root, nonce, index, programMetadata(verified), 0, 1, [])
+      .accounts({
+        ...(await stakeAccounts(assetId)),
+        walletBlocklist,
+        mintBlocklist,
+      })
+      .remainingAccounts(proofAccounts)
+      .rpc();
+    return assetId;
+  };
+
+  before(async () => {
+    if ((await connection.getAccountInfo(globalPool)) === null) {
+      await program.methods
+        .initialize()
+        .accounts({
+          admin: user,
+          globalPool,
+          treasury,
+          revenueVault,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    }
+    await program.methods
+      .initUser()
+      .accounts({
+        user,
+        globalPool,
+        userPool,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    // Sized collection NFT that verifies minted leaves
+    await createMint(connection, payer, user, user, 0, collectionMint);
+    const collectionToken = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      collectionMint.publicKey,
+      user
+    );
+    await mintTo(connection, payer, collectionMint.publicKey, collectionToken.address, user, 1);
+    const collectionMetadata = await getMetadata(collectionMint.publicKey);
+    const collectionEdition = await getMasterEdition(collectionMint.publicKey);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createCreateMetadataAccountV3Instruction(
+          {
+            metadata: collectionMetadata,
+            mint: collectionMint.publicKey,
+            mintAuthority: user,
+            payer: user,
+            updateAuthority: user,
+          },
+          {
+            createMetadataAccountArgsV3: {
+              data: {
+                name: "Staked cNFT Collection",
+                symbol: "SCN",
+                uri: "https://example.com/collection.json",
+                sellerFeeBasisPoints: 0,
+                creators: null,
+                collection: null,
+                uses: null,
+              },
+              isMutable: true,
+              collectionDetails: { __kind: "V1", size: 0 },
+            },
+          }
+        ),
+        createCreateMasterEditionV3Instruction(
+          {
+            edition: collectionEdition,
+            mint: collectionMint.publicKey,
+            updateAuthority: user,
+            mintAuthority: user,
+            payer: user,
+            metadata: collectionMetadata,
+          },
+          { createMasterEditionArgs: { maxSupply: 0 } }
+        )
+      )
+    );
+
+    // Tree without canopy so the whole proof is passed
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        await createAllocTreeIx(
+          connection,
+          merkleTree.publicKey,
+          user,
+          { maxDepth: MAX_DEPTH, maxBufferSize: MAX_BUFFER_SIZE },
+          0
+        ),
+        createCreateTreeInstruction(
+          {
+            treeAuthority,
+            merkleTree: merkleTree.publicKey,
+            payer: user,
+            treeCreator: user,
+            logWrapper: SPL_NOOP_PROGRAM_ID,
+            compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          },
+          { maxDepth: MAX_DEPTH, maxBufferSize: MAX_BUFFER_SIZE, public: false }
+        )
+      ),
+      [merkleTree]
+    );
+
+    // Leaf 0 is a verified collection member
+    const [bubblegumSigner] = PublicKey.findProgramAddressSync(
+      [Buffer.from("collection_cpi")],
+      BUBBLEGUM_PROGRAM_ID
+    );
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createMintToCollectionV1Instruction(
+          {
+            treeAuthority,
+            leafOwner: user,
+            leafDelegate: user,
+            merkleTree: merkleTree.publicKey,
+            payer: user,
+            treeDelegate: user,
+            collectionAuthority: user,
+            collectionAuthorityRecordPda: BUBBLEGUM_PROGRAM_ID,
+            collectionMint: collectionMint.publicKey,
+            collectionMetadata,
+            editionAccount: collectionEdition,
+            bubblegumSigner,
+            logWrapper: SPL_NOOP_PROGRAM_ID,
+            compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+            tokenMetadataProgram: METAPLEX,
+          },
+          { metadataArgs: metadataArgs(false) }
+        )
+      )
+    );
+    leaves.push(
+      computeCompressedNFTHash(
+        await getLeafAssetId(merkleTree.publicKey, new anchor.BN(0)),
+        user,
+        user,
+        new anchor.BN(0),
+        metadataArgs(true)
+      )
+    );
+
+    // Leaf 1 claims the collection without verification
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createMintV1Instruction(
+          {
+            treeAuthority,
+            leafOwner: user,
+            leafDelegate: user,
+            merkleTree: merkleTree.publicKey,
+            payer: user,
+            treeDelegate: user,
+            logWrapper: SPL_NOOP_PROGRAM_ID,
+            compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          },
+          { message: metadataArgs(false) }
+        )
+      )
+    );
+    leaves.push(
+      computeCompressedNFTHash(
+        await getLeafAssetId(merkleTree.publicKey, new anchor.BN(1)),
+        user,
+        user,
+        new anchor.BN(1),
+        metadataArgs(false)
+      )
+    );
+  });
+
+  it("rejects cNFT not verified in collection", async () => {
+    try {
+      await lockCnft(1, false);
+      assert.fail("unverified cNFT was staked");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "InvalidCollection");
+    }
+  });
+
+  it("stakes cNFT by moving leaf to user pool", async () => {
+    const assetId = await lockCnft(0, true);
+
+    const [stakedNft] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKED_NFT_SEED), assetId.toBuffer()],
+      program.programId
+    );
+    const stakedInfo = await program.account.stakedNft.fetch(stakedNft);
+    assert.ok(stakedInfo.owner.equals(user));
+    assert.ok(stakedInfo.mint.equals(assetId));
+    assert.deepEqual(stakedInfo.custody, { compressedEscrow: {} });
+
+    const userInfo = await program.account.userPool.fetch(userPool);
+    assert.equal(userInfo.stakeCnt, 1);
+
+    // User pool owns and delegates the leaf while staked
+    leaves[0] = computeCompressedNFTHash(
+      assetId,
+      userPool,
+      userPool,
+      new anchor.BN(0),
+      metadataArgs(true)
+    );
+    const tree = await ConcurrentMerkleTreeAccount.fromAccountAddress(
+      connection,
+      merkleTree.publicKey
+    );
+    assert.ok(
+      tree.getCurrentRoot().equals(
+        MerkleTree.sparseMerkleTreeFromLeaves(leaves, MAX_DEPTH).getRoot()
+      )
+    );
+  });
+
+  it("unstakes cNFT back to user", async () => {
+    const nonce = new anchor.BN(0);
+    const assetId = await getLeafAssetId(merkleTree.publicKey, nonce);
+    const { root, proofAccounts } = await getProof(0);
+    const accounts = await stakeAccounts(assetId);
+
+    await program.methods
+      .unlockCnft(assetId, root, nonce, 0, programMetadata(true))
+      .accounts(accounts)
+      .remainingAccounts(proofAccounts)
+      .rpc();
+
+    assert.isNull(await connection.getAccountInfo(accounts.stakedNft));
+    const userInfo = await program.account.userPool.fetch(userPool);
+    assert.equal(userInfo.stakeCnt, 0);
+
+    leaves[0] = computeCompressedNFTHash(assetId, user, user, nonce, metadataArgs(true));
+    const tree = await ConcurrentMerkleTreeAccount.fromAccountAddress(
+      connection,
+      merkleTree.publicKey
+    );
+    assert.ok(
+      tree.getCurrentRoot().equals(
+        MerkleTree.sparseMerkleTreeFromLeaves(leaves, MAX_DEPTH).getRoot()
+      )
+    );
+  });
+
+  it("force unlocks blocked cNFT back to owner", async () => {
+    const nonce = new anchor.BN(0);
+    const assetId = await lockCnft(0, true);
+    leaves[0] = computeCompressedNFTHash(
+      assetId,
+      userPool,
+      userPool,
+      nonce,
+      metadataArgs(true)
+    );
+
+    const { root, proofAccounts } = await getProof(0);
+    const accounts = await stakeAccounts(assetId);
+    const [mintBlocklist] = PublicKey.findProgramAddressSync(
+      [Buffer.from(BLOCKLIST_SEED), assetId.toBuffer()],
+      program.programId
+    );
+    const forceUnlock = () =>
+      program.methods
+        .forceUnlockCnft(assetId, root, nonce, 0, programMetadata(true))
+        .accounts({ ...accounts, mintBlocklist, owner: user })
+        .remainingAccounts(proofAccounts)
+        .rpc();
+
+    // Only blocked cNFTs can be force unlocked
+    try {
+      await forceUnlock();
+      assert.fail("cNFT that isn't blocked was force unlocked");
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, "AccountNotInitialized");
+    }
+
+    await addBlocklist(assetId);
+    await forceUnlock();
+
+    assert.isNull(await connection.getAccountInfo(accounts.stakedNft));
+    const userInfo = await program.account.userPool.fetch(userPool);
+    assert.equal(userInfo.stakeCnt, 0);
+
+    leaves[0] = computeCompressedNFTHash(assetId, user, user, nonce, metadataArgs(true));
+    const tree = await ConcurrentMerkleTreeAccount.fromAccountAddress(
+      connection,
+      merkleTree.publicKey
+    );
+    assert.ok(
+      tree.getCurrentRoot().equals(
+        MerkleTree.sparseMerkleTreeFromLeaves(leaves, MAX_DEPTH).getRoot()
+      )
+    );
+    await removeBlocklist(assetId);
+  });
+});
@@ -0,0 +1,159 @@
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { createUmi } from "@metaplex-foundation/umi-bundle-defaults";
+import {
+  createSignerFromKeypair,
+  generateSigner,
+  keypairIdentity,
+  publicKey,
+} from "@metaplex-foundation/umi";
+import {
+  fromWeb3JsKeypair,
+  toWeb3JsPublicKey,
+} from "@metaplex-foundation/umi-web3js-adapters";
+import {
+  create,
+  createCollection,
+  fetchAsset,
+  fetchCollection,
+  mplCore,
+  MPL_CORE_PROGRAM_ID,
+} from "@metaplex-foundation/mpl-core";
+import { assert } from "chai";
+
+import {
+  addBlocklist,
+  admin,
+  blocklistOf,
+  checkpointOf,
+  connection,
+  ensureInitialized,
+  expectError,
+  globalPool,
+  loadKeypair,
+  newUser,
+  program,
+  removeBlocklist,
+  stakedNftOf,
+  treasury,
+  userPoolOf,
+} from "./helpers";
+
+describe("core assets", () => {
+  const umi = createUmi(connection.rpcEndpoint)
+    .use(mplCore())
+    .use(keypairIdentity(fromWeb3JsKeypair(admin)));
+
+  // Collection the program is built to accept with localnet feature
+  const collectionKeypair = loadKeypair("tests/fixtures/core-collection.json");
+  const collection = collectionKeypair.publicKey;
+
+  let user: Keypair;
+
+  // Asset owned by owner, in the collection unless told otherwise
+  const mintAsset = async (owner: PublicKey, inCollection = true) => {
+    const asset = generateSigner(umi);
+    await create(umi, {
+      asset,
+      name: "Staked Core Asset",
+      uri: "https://example.com/core.json",
+      owner: publicKey(owner),
+      collection: inCollection ? await fetchCollection(umi, publicKey(collection)) : undefined,
+    }).sendAndConfirm(umi);
+    return toWeb3JsPublicKey(asset.publicKey);
+  };
+
+  const coreAccounts = async (owner: PublicKey, asset: PublicKey) => ({
+    admin: admin.publicKey,
+    globalPool,
+    userPool: userPoolOf(owner),
+    asset,
+    collection,
+    stakedNft: stakedNftOf(asset),
+    stakeCheckpoint: await checkpointOf(owner),
+    treasury,
+    coreProgram: toWeb3JsPublicKey(MPL_CORE_PROGRAM_ID),
+    systemProgram: SystemProgram.programId,
+  });
+
+  const lockCore = async (asset: PublicKey) =>
+    program.methods
+      .lockCore(0, 0, [])
+      .accounts({
+        ...(await coreAccounts(user.publicKey, asset)),
+        signer: user.publicKey,
+        walletBlocklist: blocklistOf(user.publicKey),
+        mintBlocklist: blocklistOf(asset),
+      })
+      .signers([user])
+      .rpc();
+
+  const unlockCore = async (asset: PublicKey) =>
+    program.methods
+      .unlockCore()
+      .accounts({ ...(await coreAccounts(user.publicKey, asset)), signer: user.publicKey })
+      .signers([user])
+      .rpc();
+
+  const freezeDelegate = async (asset: PublicKey) =>
+    (await fetchAsset(umi, publicKey(asset))).freezeDelegate;
+
+  before(async () => {
+    await ensureInitialized();
+    if ((await connection.getAccountInfo(collection)) === null) {
+      await createCollection(umi, {
+        collection: createSignerFromKeypair(umi, fromWeb3JsKeypair(collectionKeypair)),
+        name: "Staked Core Collection",
+        uri: "https://example.com/core-collection.json",
+      }).sendAndConfirm(umi);
+    }
+    user = await newUser();
+  });
+
+  it("freezes asset with user pool as freeze delegate", async () => {
+    const asset = await mintAsset(user.publicKey);
+    await lockCore(asset);
+
+    const plugin = await freezeDelegate(asset);
+    assert.isTrue(plugin.frozen);
+    assert.equal(plugin.authority.type, "Address");
+    assert.equal(plugin.authority.address, userPoolOf(user.publicKey).toBase58());
+
+    const stakedInfo = await program.account.stakedNft.fetch(stakedNftOf(asset));
+    assert.deepEqual(stakedInfo.custody, { coreFreeze: {} });
+    let userInfo = await program.account.userPool.fetch(userPoolOf(user.publicKey));
+    assert.equal(userInfo.stakeCnt, 1);
+
+    await unlockCore(asset);
+
+    const thawed = await freezeDelegate(asset);
+    assert.isFalse(thawed.frozen);
+    assert.equal(thawed.authority.type, "Owner");
+    assert.isNull(await connection.getAccountInfo(stakedNftOf(asset)));
+    userInfo = await program.account.userPool.fetch(userPoolOf(user.publicKey));
+    assert.equal(userInfo.stakeCnt, 0);
+  });
+
+  it("rejects asset outside collection or of another owner", async () => {
+    await expectError(lockCore(await mintAsset(user.publicKey, false)), "InvalidCollection");
+    await expectError(lockCore(await mintAsset(admin.publicKey)), "NftNotExist");
+  });
+
+  it("force unlocks blocked asset", async () => {
+    const asset = await mintAsset(user.publicKey);
+    await lockCore(asset);
+    await addBlocklist(asset);
+
+    await program.methods
+      .forceUnlockCore()
+      .accounts({
+        ...(await coreAccounts(user.publicKey, asset)),
+        mintBlocklist: blocklistOf(asset),
+        owner: user.publicKey,
+      })
+      .rpc();
+
+    assert.isFalse((await freezeDelegate(asset)).frozen);
+    assert.isNull(await connection.getAccountInfo(stakedNftOf(asset)));
+    await removeBlocklist(asset);
+  });
+});
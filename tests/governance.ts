@@ -0,0 +1,93 @@
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { assert } from "chai";
+
+import {
+  chainTime,
+  ensureInitialized,
+  expectError,
+  findPda,
+  lockPnft,
+  mintPnft,
+  newUser,
+  program,
+  unlockPnft,
+  userPoolOf,
+} from "./helpers";
+import { VOTER_WEIGHT_RECORD_SEED } from "../lib/constant";
+
+const GOVERNANCE_VOTE_LOCK = 3 * 24 * 60 * 60;
+
+describe("governance voter weight", () => {
+  const realm = Keypair.generate().publicKey;
+  const governingTokenMint = Keypair.generate().publicKey;
+  const proposal = Keypair.generate().publicKey;
+
+  let voter: Keypair;
+  let voterMint: PublicKey;
+
+  const recordOf = (owner: PublicKey) =>
+    findPda([
+      Buffer.from(VOTER_WEIGHT_RECORD_SEED),
+      realm.toBuffer(),
+      governingTokenMint.toBuffer(),
+      owner.toBuffer(),
+    ]);
+
+  const updateVoterWeightRecord = (owner: Keypair, action: any, target: PublicKey | null) =>
+    program.methods
+      .updateVoterWeightRecord(realm, governingTokenMint, action, target)
+      .accounts({
+        payer: owner.publicKey,
+        owner: owner.publicKey,
+        userPool: userPoolOf(owner.publicKey),
+        voterWeightRecord: recordOf(owner.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  before(async () => {
+    await ensureInitialized();
+    voter = await newUser();
+    voterMint = await mintPnft(voter.publicKey);
+    await lockPnft(voter, voterMint);
+  });
+
+  it("weighs voter by staked NFTs without locking them for other actions", async () => {
+    const user = await newUser();
+    const mint = await mintPnft(user.publicKey);
+    await lockPnft(user, mint);
+
+    await updateVoterWeightRecord(user, { createProposal: {} }, null);
+    const record = await program.account.voterWeightRecord.fetch(recordOf(user.publicKey));
+    assert.ok(record.realm.equals(realm));
+    assert.ok(record.governingTokenOwner.equals(user.publicKey));
+    assert.equal(record.voterWeight.toNumber(), 1);
+    assert.deepEqual(record.weightAction, { createProposal: {} });
+
+    await unlockPnft(user, mint);
+  });
+
+  it("requires proposal to cast vote", async () => {
+    await expectError(
+      updateVoterWeightRecord(voter, { castVote: {} }, null),
+      "ActionTargetRequired"
+    );
+  });
+
+  it("locks voted NFTs so they can't vote again from another wallet", async () => {
+    await updateVoterWeightRecord(voter, { castVote: {} }, proposal);
+
+    const record = await program.account.voterWeightRecord.fetch(recordOf(voter.publicKey));
+    assert.equal(record.voterWeight.toNumber(), 1);
+    assert.ok(record.weightActionTarget.equals(proposal));
+
+    const userInfo = await program.account.userPool.fetch(userPoolOf(voter.publicKey));
+    assert.isAtLeast(
+      userInfo.voteLockEnd.toNumber(),
+      (await chainTime()) + GOVERNANCE_VOTE_LOCK - 60
+    );
+
+    await expectError(unlockPnft(voter, voterMint), "VoteActive");
+  });
+});
@@ -0,0 +1,358 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import {
+  ComputeBudgetProgram,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import {
+  createCreateInstruction,
+  createMintInstruction,
+  TokenRecord,
+  TokenStandard,
+} from "@metaplex-foundation/mpl-token-metadata";
+import { assert } from "chai";
+import * as fs from "fs";
+
+import { NftStaking } from "../target/types/nft_staking";
+import {
+  METAPLEX,
+  findTokenRecordPda,
+  getMasterEdition,
+  getMetadata,
+} from "../lib/util";
+import {
+  BLOCKLIST_SEED,
+  CONFIG_CHANGE_SEED,
+  GLOBAL_AUTHORITY_SEED,
+  REVENUE_SEED,
+  REWARD_VAULT_SEED,
+  STAKE_CHECKPOINT_SEED,
+  STAKED_NFT_SEED,
+  TREASURY_SEED,
+  USER_POOL_SEED,
+} from "../lib/constant";
+
+// Shared by every test file, they all run against one local validator
+export const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+
+export const program = anchor.workspace.NftStaking as Program<NftStaking>;
+export const connection = provider.connection;
+export const admin = (provider.wallet as anchor.Wallet).payer;
+
+export const loadKeypair = (path: string) =>
+  Keypair.fromSecretKey(Uint8Array.from(JSON.parse(fs.readFileSync(path, "utf-8"))));
+
+// Verified creator the program is built to accept with localnet feature
+export const pnftCreator = loadKeypair("tests/fixtures/pnft-creator.json");
+
+export const findPda = (seeds: (Buffer | Uint8Array)[]) =>
+  PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+export const globalPool = findPda([Buffer.from(GLOBAL_AUTHORITY_SEED)]);
+export const treasury = findPda([Buffer.from(TREASURY_SEED)]);
+export const revenueVault = findPda([Buffer.from(REVENUE_SEED)]);
+
+export const userPoolOf = (user: PublicKey) =>
+  findPda([user.toBuffer(), Buffer.from(USER_POOL_SEED)]);
+export const stakedNftOf = (mint: PublicKey) =>
+  findPda([Buffer.from(STAKED_NFT_SEED), mint.toBuffer()]);
+export const blocklistOf = (address: PublicKey) =>
+  findPda([Buffer.from(BLOCKLIST_SEED), address.toBuffer()]);
+
+export const checkpointOf = async (user: PublicKey) => {
+  const globalInfo = await program.account.globalPool.fetch(globalPool);
+  const epoch = Buffer.alloc(4);
+  epoch.writeUInt32LE(globalInfo.epoch as number);
+  return findPda([Buffer.from(STAKE_CHECKPOINT_SEED), user.toBuffer(), epoch]);
+};
+
+export const rewardVaultOf = (index: number) =>
+  findPda([Buffer.from(REWARD_VAULT_SEED), Buffer.from([index])]);
+
+export const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+// Fails unless the promise is rejected with the program error code
+export const expectError = async (promise: Promise<unknown>, code: string) => {
+  let error;
+  try {
+    await promise;
+  } catch (err) {
+    error = err;
+  }
+  assert.isDefined(error, `expected ${code}`);
+  assert.equal(error.error?.errorCode?.code, code, String(error));
+};
+
+export const ensureInitialized = async () => {
+  if ((await connection.getAccountInfo(globalPool)) !== null) {
+    return;
+  }
+  await program.methods
+    .initialize()
+    .accounts({
+      admin: admin.publicKey,
+      globalPool,
+      treasury,
+      revenueVault,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .rpc();
+};
+
+// Validator clock, program checks times against it rather than local time
+export const chainTime = async () => connection.getBlockTime(await connection.getSlot());
+
+export const airdrop = async (address: PublicKey, sol = 10) => {
+  const signature = await connection.requestAirdrop(address, sol * LAMPORTS_PER_SOL);
+  const latest = await connection.getLatestBlockhash();
+  await connection.confirmTransaction({ signature, ...latest });
+};
+
+// Funded wallet with its user pool, so test files don't share stake state
+export const newUser = async () => {
+  const user = Keypair.generate();
+  await airdrop(user.publicKey);
+  await program.methods
+    .initUser()
+    .accounts({
+      user: user.publicKey,
+      globalPool,
+      userPool: userPoolOf(user.publicKey),
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .signers([user])
+    .rpc();
+  return user;
+};
+
+// Config delay is zero with localnet feature, so queued change applies right away
+export const applyConfig = async (change: any) => {
+  const globalInfo = await program.account.globalPool.fetch(globalPool);
+  const pendingChange = findPda([
+    Buffer.from(CONFIG_CHANGE_SEED),
+    globalInfo.changeCnt.toArrayLike(Buffer, "le", 8),
+  ]);
+  await program.methods
+    .queueConfigChange(change)
+    .accounts({
+      admin: admin.publicKey,
+      globalPool,
+      pendingChange,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .rpc();
+  await program.methods
+    .executeConfigChange()
+    .accounts({ admin: admin.publicKey, globalPool, pendingChange })
+    .rpc();
+};
+
+export const addBlocklist = (address: PublicKey) =>
+  program.methods
+    .addBlocklist(address)
+    .accounts({
+      admin: admin.publicKey,
+      globalPool,
+      blocklistEntry: blocklistOf(address),
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .rpc();
+
+export const removeBlocklist = (address: PublicKey) =>
+  program.methods
+    .removeBlocklist()
+    .accounts({ admin: admin.publicKey, globalPool, blocklistEntry: blocklistOf(address) })
+    .rpc();
+
+/**
+ * Reward stream of a new token that emits rate per second for one NFT at base multiplier
+ * streams can't be removed, so the stream ends after duration
+ */
+export const addRewardStream = async (rate: number, duration: number, amount: number) => {
+  const globalInfo = await program.account.globalPool.fetch(globalPool);
+  const index = globalInfo.streamCnt;
+  const rewardMint = await createMint(connection, admin, admin.publicKey, null, 0);
+  const adminTokenAccount = await getOrCreateAssociatedTokenAccount(
+    connection,
+    admin,
+    rewardMint,
+    admin.publicKey
+  );
+  await mintTo(connection, admin, rewardMint, adminTokenAccount.address, admin, amount);
+
+  const start = (await chainTime()) + 1;
+  const rewardVault = rewardVaultOf(index);
+  await program.methods
+    .addRewardStream(new anchor.BN(rate), new anchor.BN(start), new anchor.BN(start + duration))
+    .accounts({
+      admin: admin.publicKey,
+      globalPool,
+      rewardMint,
+      rewardVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .rpc();
+  await program.methods
+    .fundRewards(index, new anchor.BN(amount), new anchor.BN(start + duration))
+    .accounts({
+      admin: admin.publicKey,
+      globalPool,
+      rewardVault,
+      adminTokenAccount: adminTokenAccount.address,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .rpc();
+  return { index, rewardMint, rewardVault };
+};
+
+// pNFT without rule set verified by the collection creator, held by owner
+export const mintPnft = async (owner: PublicKey) => {
+  const mint = Keypair.generate();
+  const metadata = await getMetadata(mint.publicKey);
+  const masterEdition = await getMasterEdition(mint.publicKey);
+  const token = getAssociatedTokenAddressSync(mint.publicKey, owner);
+
+  await provider.sendAndConfirm(
+    new Transaction().add(
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+      createCreateInstruction(
+        {
+          metadata,
+          masterEdition,
+          mint: mint.publicKey,
+          authority: pnftCreator.publicKey,
+          payer: admin.publicKey,
+          updateAuthority: pnftCreator.publicKey,
+          systemProgram: SystemProgram.programId,
+          sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          splTokenProgram: TOKEN_PROGRAM_ID,
+        },
+        {
+          createArgs: {
+            __kind: "V1",
+            assetData: {
+              name: "Staked pNFT",
+              symbol: "SPN",
+              uri: "https://example.com/pnft.json",
+              sellerFeeBasisPoints: 0,
+              creators: [{ address: pnftCreator.publicKey, verified: true, share: 100 }],
+              primarySaleHappened: false,
+              isMutable: true,
+              tokenStandard: TokenStandard.ProgrammableNonFungible,
+              collection: null,
+              uses: null,
+              collectionDetails: null,
+              ruleSet: null,
+            },
+            decimals: 0,
+            printSupply: { __kind: "Zero" },
+          },
+        }
+      ),
+      createMintInstruction(
+        {
+          token,
+          tokenOwner: owner,
+          metadata,
+          masterEdition,
+          tokenRecord: findTokenRecordPda(mint.publicKey, token),
+          mint: mint.publicKey,
+          authority: pnftCreator.publicKey,
+          payer: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+          sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          splTokenProgram: TOKEN_PROGRAM_ID,
+          splAtaProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        },
+        { mintArgs: { __kind: "V1", amount: 1, authorizationData: null } }
+      )
+    ),
+    [mint, pnftCreator]
+  );
+  return mint.publicKey;
+};
+
+// Token metadata accounts of a pNFT held by owner, program id stands for omitted accounts
+export const pnftAccounts = async (owner: PublicKey, mint: PublicKey) => {
+  const tokenAccount = getAssociatedTokenAddressSync(mint, owner);
+  return {
+    admin: admin.publicKey,
+    globalPool,
+    tokenAccount,
+    tokenMint: mint,
+    tokenMintEdition: await getMasterEdition(mint),
+    tokenMintRecord: findTokenRecordPda(mint, tokenAccount),
+    mintMetadata: await getMetadata(mint),
+    // pNFTs are minted without rule set, token metadata reads its own id as none
+    authRules: METAPLEX,
+    sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+    signer: owner,
+    userPool: userPoolOf(owner),
+    stakedNft: stakedNftOf(mint),
+    stakeCheckpoint: await checkpointOf(owner),
+    escrowTokenAccount: program.programId,
+    receiptMint: program.programId,
+    receiptTokenAccount: program.programId,
+    receiptEscrow: program.programId,
+    receiptEscrowRecord: program.programId,
+    treasury,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    tokenMetadataProgram: METAPLEX,
+    authRulesProgram: METAPLEX,
+    associatedTokenProgram: program.programId,
+    systemProgram: SystemProgram.programId,
+  };
+};
+
+export const tokenRecordOf = (owner: PublicKey, mint: PublicKey) =>
+  TokenRecord.fromAccountAddress(
+    connection,
+    findTokenRecordPda(mint, getAssociatedTokenAddressSync(mint, owner))
+  );
+
+export type LockOptions = {
+  tier?: number;
+  weight?: number;
+  proof?: number[][];
+};
+
+export const lockPnft = async (owner: Keypair, mint: PublicKey, options: LockOptions = {}) =>
+  program.methods
+    .lockPnft(options.tier ?? 0, options.weight ?? 0, options.proof ?? [], false)
+    .accounts({
+      ...(await pnftAccounts(owner.publicKey, mint)),
+      walletBlocklist: blocklistOf(owner.publicKey),
+      mintBlocklist: blocklistOf(mint),
+    })
+    .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+    .signers([owner])
+    .rpc();
+
+export const unlockPnft = async (owner: Keypair, mint: PublicKey) =>
+  program.methods
+    .unlockPnft()
+    .accounts(await pnftAccounts(owner.publicKey, mint))
+    .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+    .signers([owner])
+    .rpc();
@@ -0,0 +1,55 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+
+import {
+  ensureInitialized,
+  expectError,
+  globalPool,
+  lockPnft,
+  mintPnft,
+  newUser,
+  program,
+  stakedNftOf,
+  unlockPnft,
+  userPoolOf,
+} from "./helpers";
+
+const DAY_SECONDS = 24 * 60 * 60;
+
+describe("lock tiers", () => {
+  let user: Keypair;
+  let mint: PublicKey;
+
+  before(async () => {
+    await ensureInitialized();
+    user = await newUser();
+    mint = await mintPnft(user.publicKey);
+  });
+
+  it("rejects unknown tier", async () => {
+    await expectError(lockPnft(user, mint, { tier: 4 }), "InvalidLockTier");
+  });
+
+  it("records committed tier with its multiplier", async () => {
+    const globalInfo = await program.account.globalPool.fetch(globalPool);
+    const tier = globalInfo.lockTiers[1];
+    assert.isAbove(tier.durationDays, 0);
+
+    await lockPnft(user, mint, { tier: 1 });
+
+    const stakedInfo = await program.account.stakedNft.fetch(stakedNftOf(mint));
+    assert.equal(
+      stakedInfo.lockEnd.toNumber(),
+      stakedInfo.stakedAt.toNumber() + tier.durationDays * DAY_SECONDS
+    );
+    assert.equal(stakedInfo.multiplierBps, tier.multiplierBps);
+    assert.equal(stakedInfo.weight.toNumber(), stakedInfo.rarityWeight * tier.multiplierBps);
+
+    const userInfo = await program.account.userPool.fetch(userPoolOf(user.publicKey));
+    assert.equal(userInfo.stakeWeight.toNumber(), stakedInfo.weight.toNumber());
+  });
+
+  it("refuses to unlock before committed end", async () => {
+    await expectError(unlockPnft(user, mint), "StillLocked");
+  });
+});
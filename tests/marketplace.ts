@@ -0,0 +1,102 @@
+import { ComputeBudgetProgram, Keypair, PublicKey } from "@solana/web3.js";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { TokenState } from "@metaplex-foundation/mpl-token-metadata";
+import { assert } from "chai";
+
+import {
+  applyConfig,
+  blocklistOf,
+  ensureInitialized,
+  expectError,
+  globalPool,
+  lockPnft,
+  mintPnft,
+  newUser,
+  pnftAccounts,
+  program,
+  stakedNftOf,
+  tokenRecordOf,
+  unlockPnft,
+} from "./helpers";
+import { findTokenRecordPda } from "../lib/util";
+
+// Selling through an allowlisted marketplace needs a marketplace program calling in by CPI,
+// these tests cover the paths a plain transaction can reach
+describe("marketplace sales", () => {
+  let seller: Keypair;
+  let buyer: Keypair;
+
+  const computeLimit = ComputeBudgetProgram.setComputeUnitLimit({ units: 600_000 });
+
+  const sellPnft = async (mint: PublicKey, accounts: object = {}) => {
+    const buyerTokenAccount = getAssociatedTokenAddressSync(mint, buyer.publicKey);
+    return program.methods
+      .sellPnft()
+      .accounts({
+        ...(await pnftAccounts(seller.publicKey, mint)),
+        buyer: buyer.publicKey,
+        buyerTokenAccount,
+        buyerTokenRecord: findTokenRecordPda(mint, buyerTokenAccount),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        ...accounts,
+      })
+      .preInstructions([computeLimit])
+      .signers([seller])
+      .rpc();
+  };
+
+  before(async () => {
+    await ensureInitialized();
+    seller = await newUser();
+    buyer = await newUser();
+  });
+
+  after(async () => {
+    await applyConfig({ marketplace: { index: 0, program: PublicKey.default } });
+  });
+
+  it("rejects sale called outside marketplace", async () => {
+    const mint = await mintPnft(seller.publicKey);
+    await lockPnft(seller, mint);
+
+    await expectError(sellPnft(mint), "UnauthorizedCaller");
+
+    // Staking program can't stand in for marketplace even when allowlisted
+    await applyConfig({ marketplace: { index: 0, program: program.programId } });
+    await expectError(sellPnft(mint), "UnauthorizedCaller");
+
+    const record = await tokenRecordOf(seller.publicKey, mint);
+    assert.equal(record.state, TokenState.Locked);
+    assert.ok(
+      (await program.account.stakedNft.fetch(stakedNftOf(mint))).owner.equals(seller.publicKey)
+    );
+    await unlockPnft(seller, mint);
+  });
+
+  it("rejects sale of receipt position", async () => {
+    const mint = await mintPnft(seller.publicKey);
+    const receiptMint = Keypair.generate();
+    const receiptEscrow = getAssociatedTokenAddressSync(mint, globalPool, true);
+    await program.methods
+      .lockPnft(0, 0, [], true)
+      .accounts({
+        ...(await pnftAccounts(seller.publicKey, mint)),
+        stakeCheckpoint: program.programId,
+        receiptMint: receiptMint.publicKey,
+        receiptTokenAccount: getAssociatedTokenAddressSync(
+          receiptMint.publicKey,
+          seller.publicKey
+        ),
+        receiptEscrow,
+        receiptEscrowRecord: findTokenRecordPda(mint, receiptEscrow),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        walletBlocklist: blocklistOf(seller.publicKey),
+        mintBlocklist: blocklistOf(mint),
+      })
+      .preInstructions([computeLimit])
+      .signers([seller, receiptMint])
+      .rpc();
+
+    await expectError(sellPnft(mint), "SaleCustodyUnsupported");
+  });
+});
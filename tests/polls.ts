@@ -0,0 +1,116 @@
+import * as anchor from "@project-serum/anchor";
+import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { assert } from "chai";
+
+import {
+  admin,
+  chainTime,
+  ensureInitialized,
+  expectError,
+  findPda,
+  globalPool,
+  lockPnft,
+  mintPnft,
+  newUser,
+  program,
+  sleep,
+  unlockPnft,
+  userPoolOf,
+} from "./helpers";
+import { POLL_SEED, VOTE_RECORD_SEED } from "../lib/constant";
+
+describe("staker polls", () => {
+  const pollDuration = 20;
+
+  let voter: Keypair;
+  let mints: PublicKey[];
+  let poll: PublicKey;
+
+  const voteRecordOf = (wallet: PublicKey) =>
+    findPda([Buffer.from(VOTE_RECORD_SEED), poll.toBuffer(), wallet.toBuffer()]);
+
+  const castVote = (wallet: Keypair, option: number) =>
+    program.methods
+      .castVote(option)
+      .accounts({
+        signer: wallet.publicKey,
+        userPool: userPoolOf(wallet.publicKey),
+        poll,
+        voteRecord: voteRecordOf(wallet.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([wallet])
+      .rpc();
+
+  before(async () => {
+    await ensureInitialized();
+    voter = await newUser();
+    mints = [await mintPnft(voter.publicKey), await mintPnft(voter.publicKey)];
+    for (const mint of mints) {
+      await lockPnft(voter, mint);
+    }
+
+    const id = Math.floor(Math.random() * 2 ** 32);
+    const idBytes = Buffer.alloc(4);
+    idBytes.writeUInt32LE(id);
+    poll = findPda([Buffer.from(POLL_SEED), idBytes]);
+    const now = await chainTime();
+    await program.methods
+      .createPoll(id, 3, new anchor.BN(now - 60), new anchor.BN(now + pollDuration))
+      .accounts({
+        admin: admin.publicKey,
+        globalPool,
+        poll,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  });
+
+  it("rejects unknown option and voters without stake", async () => {
+    await expectError(castVote(voter, 3), "InvalidPollOption");
+    await expectError(castVote(await newUser(), 0), "NftNotExist");
+  });
+
+  it("weighs vote by stake count and locks voted NFTs", async () => {
+    await castVote(voter, 1);
+
+    const pollInfo = await program.account.poll.fetch(poll);
+    assert.deepEqual(
+      pollInfo.votes.slice(0, 3).map((votes) => votes.toNumber()),
+      [0, 2, 0]
+    );
+    const record = await program.account.voteRecord.fetch(voteRecordOf(voter.publicKey));
+    assert.equal(record.weight.toNumber(), 2);
+    assert.equal(record.option, 1);
+
+    const userInfo = await program.account.userPool.fetch(userPoolOf(voter.publicKey));
+    assert.equal(userInfo.voteLockEnd.toNumber(), pollInfo.endTime.toNumber());
+    await expectError(unlockPnft(voter, mints[0]), "VoteActive");
+  });
+
+  it("doesn't count a second vote", async () => {
+    let error;
+    try {
+      await castVote(voter, 2);
+    } catch (err) {
+      error = err;
+    }
+    assert.isDefined(error, "second vote was accepted");
+    assert.ok(error.logs?.some((log: string) => log.includes("already in use")));
+
+    const pollInfo = await program.account.poll.fetch(poll);
+    assert.equal(pollInfo.votes[2].toNumber(), 0);
+  });
+
+  it("releases NFTs once poll ends", async () => {
+    await sleep((pollDuration + 1) * 1000);
+    await expectError(castVote(await newUser(), 0), "PollNotActive");
+
+    for (const mint of mints) {
+      await unlockPnft(voter, mint);
+    }
+    const userInfo = await program.account.userPool.fetch(userPoolOf(voter.publicKey));
+    assert.equal(userInfo.stakeCnt, 0);
+  });
+});
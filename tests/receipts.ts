@@ -0,0 +1,222 @@
+import { ComputeBudgetProgram, Keypair, PublicKey, Transaction } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createTransferInstruction,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMint,
+} from "@solana/spl-token";
+import { TokenState } from "@metaplex-foundation/mpl-token-metadata";
+import { assert } from "chai";
+
+import {
+  addBlocklist,
+  addRewardStream,
+  admin,
+  blocklistOf,
+  connection,
+  ensureInitialized,
+  expectError,
+  globalPool,
+  mintPnft,
+  newUser,
+  pnftAccounts,
+  program,
+  provider,
+  removeBlocklist,
+  sleep,
+  stakedNftOf,
+  tokenRecordOf,
+  userPoolOf,
+} from "./helpers";
+import { findTokenRecordPda } from "../lib/util";
+
+describe("receipt positions", () => {
+  let streamIndex: number;
+  let staker: Keypair;
+  let holder: Keypair;
+
+  // Token metadata accounts of the pNFT for wallet, with the escrow and receipt of the position
+  const receiptAccounts = async (wallet: PublicKey, mint: PublicKey, receiptMint: PublicKey) => {
+    const receiptEscrow = getAssociatedTokenAddressSync(mint, globalPool, true);
+    return {
+      ...(await pnftAccounts(wallet, mint)),
+      // Receipt positions aren't in stake count and have no checkpoint
+      stakeCheckpoint: program.programId,
+      receiptMint,
+      receiptTokenAccount: getAssociatedTokenAddressSync(receiptMint, wallet),
+      receiptEscrow,
+      receiptEscrowRecord: findTokenRecordPda(mint, receiptEscrow),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    };
+  };
+
+  const computeLimit = ComputeBudgetProgram.setComputeUnitLimit({ units: 600_000 });
+
+  const lockReceipt = async (wallet: Keypair, mint: PublicKey) => {
+    const receiptMint = Keypair.generate();
+    await program.methods
+      .lockPnft(0, 0, [], true)
+      .accounts({
+        ...(await receiptAccounts(wallet.publicKey, mint, receiptMint.publicKey)),
+        walletBlocklist: blocklistOf(wallet.publicKey),
+        mintBlocklist: blocklistOf(mint),
+      })
+      .preInstructions([computeLimit])
+      .signers([wallet, receiptMint])
+      .rpc();
+    return receiptMint.publicKey;
+  };
+
+  const unlockReceipt = async (wallet: Keypair, mint: PublicKey, receiptMint: PublicKey) =>
+    program.methods
+      .unlockPnft()
+      .accounts(await receiptAccounts(wallet.publicKey, mint, receiptMint))
+      .preInstructions([computeLimit])
+      .signers([wallet])
+      .rpc();
+
+  const harvestReceipt = (wallet: Keypair, mint: PublicKey, receiptMint: PublicKey) =>
+    program.methods
+      .harvestReceipt()
+      .accounts({
+        globalPool,
+        signer: wallet.publicKey,
+        userPool: userPoolOf(wallet.publicKey),
+        stakedNft: stakedNftOf(mint),
+        receiptMint,
+        receiptTokenAccount: getAssociatedTokenAddressSync(receiptMint, wallet.publicKey),
+      })
+      .signers([wallet])
+      .rpc();
+
+  // Token accounts the receipt and the pNFT are sent to must exist
+  const prepareHolder = async (mint: PublicKey, receiptMint: PublicKey) =>
+    provider.sendAndConfirm(
+      new Transaction().add(
+        createAssociatedTokenAccountIdempotentInstruction(
+          admin.publicKey,
+          getAssociatedTokenAddressSync(receiptMint, holder.publicKey),
+          holder.publicKey,
+          receiptMint
+        ),
+        createAssociatedTokenAccountIdempotentInstruction(
+          admin.publicKey,
+          getAssociatedTokenAddressSync(mint, holder.publicKey),
+          holder.publicKey,
+          mint
+        )
+      )
+    );
+
+  const sendReceipt = async (from: Keypair, to: PublicKey, receiptMint: PublicKey) =>
+    provider.sendAndConfirm(
+      new Transaction().add(
+        createTransferInstruction(
+          getAssociatedTokenAddressSync(receiptMint, from.publicKey),
+          getAssociatedTokenAddressSync(receiptMint, to),
+          from.publicKey,
+          1
+        )
+      ),
+      [from]
+    );
+
+  const rewards = async (wallet: Keypair) =>
+    (await program.account.userPool.fetch(userPoolOf(wallet.publicKey))).rewards[
+      streamIndex
+    ].toNumber();
+
+  before(async () => {
+    await ensureInitialized();
+    ({ index: streamIndex } = await addRewardStream(1_000, 60, 1_000_000_000));
+    staker = await newUser();
+    holder = await newUser();
+  });
+
+  it("pays rewards and unlock to receipt holder", async () => {
+    const mint = await mintPnft(staker.publicKey);
+    const receiptMint = await lockReceipt(staker, mint);
+
+    const stakedInfo = await program.account.stakedNft.fetch(stakedNftOf(mint));
+    assert.deepEqual(stakedInfo.custody, { receiptEscrow: {} });
+    assert.ok(stakedInfo.receiptMint.equals(receiptMint));
+    const stakerInfo = await program.account.userPool.fetch(userPoolOf(staker.publicKey));
+    assert.equal(stakerInfo.stakeCnt, 0);
+
+    await prepareHolder(mint, receiptMint);
+    await sendReceipt(staker, holder.publicKey, receiptMint);
+    await sleep(2000);
+
+    // Former staker holds no receipt and can't take the position
+    await expectError(harvestReceipt(staker, mint, receiptMint), "ReceiptRequired");
+    await expectError(unlockReceipt(staker, mint, receiptMint), "ReceiptRequired");
+
+    await harvestReceipt(holder, mint, receiptMint);
+    const harvested = await rewards(holder);
+    assert.isAbove(harvested, 0);
+    assert.equal(await rewards(staker), 0);
+
+    await unlockReceipt(holder, mint, receiptMint);
+
+    assert.isNull(await connection.getAccountInfo(stakedNftOf(mint)));
+    assert.isAtLeast(await rewards(holder), harvested);
+    assert.equal(Number((await getMint(connection, receiptMint)).supply), 0);
+    const token = await getAccount(
+      connection,
+      getAssociatedTokenAddressSync(mint, holder.publicKey)
+    );
+    assert.equal(token.amount, BigInt(1));
+    const record = await tokenRecordOf(holder.publicKey, mint);
+    assert.equal(record.state, TokenState.Unlocked);
+  });
+
+  it("doesn't let receipt of force unlocked position reach a later stake", async () => {
+    const mint = await mintPnft(staker.publicKey);
+    const oldReceipt = await lockReceipt(staker, mint);
+    await prepareHolder(mint, oldReceipt);
+    await sendReceipt(staker, holder.publicKey, oldReceipt);
+
+    // Blocked position goes back to receipt holder without rewards
+    await addBlocklist(mint);
+    await program.methods
+      .forceUnlockPnft()
+      .accounts({
+        ...(await receiptAccounts(holder.publicKey, mint, oldReceipt)),
+        mintBlocklist: blocklistOf(mint),
+        owner: holder.publicKey,
+      })
+      .preInstructions([computeLimit])
+      .rpc();
+    await removeBlocklist(mint);
+
+    const token = await getAccount(
+      connection,
+      getAssociatedTokenAddressSync(mint, holder.publicKey)
+    );
+    assert.equal(token.amount, BigInt(1));
+
+    // Holder stakes the pNFT again and sells the new receipt, old receipt is left behind
+    const newReceipt = await lockReceipt(holder, mint);
+    assert.notOk(newReceipt.equals(oldReceipt));
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createAssociatedTokenAccountIdempotentInstruction(
+          admin.publicKey,
+          getAssociatedTokenAddressSync(newReceipt, staker.publicKey),
+          staker.publicKey,
+          newReceipt
+        )
+      )
+    );
+    await sendReceipt(holder, staker.publicKey, newReceipt);
+
+    await expectError(harvestReceipt(holder, mint, oldReceipt), "ReceiptRequired");
+    await expectError(unlockReceipt(holder, mint, oldReceipt), "ReceiptRequired");
+
+    await harvestReceipt(staker, mint, newReceipt);
+    const stakedInfo = await program.account.stakedNft.fetch(stakedNftOf(mint));
+    assert.ok(stakedInfo.receiptMint.equals(newReceipt));
+  });
+});
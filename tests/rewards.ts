@@ -0,0 +1,111 @@
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+import {
+  addRewardStream,
+  admin,
+  connection,
+  ensureInitialized,
+  expectError,
+  findPda,
+  globalPool,
+  lockPnft,
+  mintPnft,
+  newUser,
+  program,
+  rewardVaultOf,
+  sleep,
+  unlockPnft,
+  userPoolOf,
+} from "./helpers";
+import { VESTING_SEED } from "../lib/constant";
+
+describe("reward streams", () => {
+  let user: Keypair;
+  let mint: PublicKey;
+  const streams: { index: number; rewardMint: PublicKey; tokenAccount: PublicKey }[] = [];
+
+  const claimReward = (index: number, tokenAccount: PublicKey) =>
+    program.methods
+      .claimReward(index)
+      .accounts({
+        globalPool,
+        signer: user.publicKey,
+        userPool: userPoolOf(user.publicKey),
+        rewardVault: rewardVaultOf(index),
+        userTokenAccount: tokenAccount,
+        vesting: findPda([
+          Buffer.from(VESTING_SEED),
+          user.publicKey.toBuffer(),
+          Buffer.from([index]),
+        ]),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+  const balance = async (tokenAccount: PublicKey) =>
+    Number((await getAccount(connection, tokenAccount)).amount);
+
+  before(async () => {
+    await ensureInitialized();
+    user = await newUser();
+    mint = await mintPnft(user.publicKey);
+
+    for (const rate of [1_000, 3_000]) {
+      const stream = await addRewardStream(rate, 30, 1_000_000_000);
+      const tokenAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        admin,
+        stream.rewardMint,
+        user.publicKey
+      );
+      streams.push({ ...stream, tokenAccount: tokenAccount.address });
+    }
+  });
+
+  it("accrues every stream while staked and settles them on unlock", async () => {
+    await lockPnft(user, mint);
+    await sleep(3000);
+    await unlockPnft(user, mint);
+
+    const userInfo = await program.account.userPool.fetch(userPoolOf(user.publicKey));
+    for (const stream of streams) {
+      assert.isAbove(userInfo.rewards[stream.index].toNumber(), 0);
+    }
+  });
+
+  it("claims each stream separately", async () => {
+    const [first, second] = streams;
+
+    await claimReward(first.index, first.tokenAccount);
+    const firstClaimed = await balance(first.tokenAccount);
+    assert.isAbove(firstClaimed, 0);
+    assert.equal(await balance(second.tokenAccount), 0);
+
+    let userInfo = await program.account.userPool.fetch(userPoolOf(user.publicKey));
+    assert.equal(userInfo.rewards[first.index].toNumber(), 0);
+    assert.isAbove(userInfo.rewards[second.index].toNumber(), 0);
+
+    await claimReward(second.index, second.tokenAccount);
+    assert.isAbove(await balance(second.tokenAccount), firstClaimed);
+
+    // Nothing accrues once unlocked
+    userInfo = await program.account.userPool.fetch(userPoolOf(user.publicKey));
+    assert.equal(userInfo.rewards[second.index].toNumber(), 0);
+    await sleep(1000);
+    await expectError(claimReward(first.index, first.tokenAccount), "NoReward");
+    assert.equal(await balance(first.tokenAccount), firstClaimed);
+  });
+
+  it("rejects tokens of another stream", async () => {
+    const [first, second] = streams;
+    await expectError(claimReward(first.index, second.tokenAccount), "ConstraintTokenMint");
+  });
+});
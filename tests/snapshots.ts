@@ -0,0 +1,85 @@
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { assert } from "chai";
+
+import {
+  admin,
+  checkpointOf,
+  ensureInitialized,
+  expectError,
+  findPda,
+  globalPool,
+  lockPnft,
+  mintPnft,
+  newUser,
+  program,
+  unlockPnft,
+  userPoolOf,
+} from "./helpers";
+import { SNAPSHOT_SEED } from "../lib/constant";
+
+describe("epoch snapshots", () => {
+  let user: Keypair;
+  let mint: PublicKey;
+
+  const snapshotOf = (epoch: number) => {
+    const epochBytes = Buffer.alloc(4);
+    epochBytes.writeUInt32LE(epoch);
+    return findPda([Buffer.from(SNAPSHOT_SEED), epochBytes]);
+  };
+
+  const takeSnapshot = async () => {
+    const globalInfo = await program.account.globalPool.fetch(globalPool);
+    const snapshot = snapshotOf(globalInfo.epoch + 1);
+    await program.methods
+      .snapshot()
+      .accounts({
+        globalPool,
+        payer: admin.publicKey,
+        snapshot,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    return snapshot;
+  };
+
+  before(async () => {
+    await ensureInitialized();
+    user = await newUser();
+    mint = await mintPnft(user.publicKey);
+    await lockPnft(user, mint);
+  });
+
+  it("records stake totals at epoch boundary", async () => {
+    const before = await program.account.globalPool.fetch(globalPool);
+    const snapshot = await takeSnapshot();
+
+    const snapshotInfo = await program.account.snapshot.fetch(snapshot);
+    assert.equal(snapshotInfo.epoch, before.epoch + 1);
+    assert.equal(snapshotInfo.totalStakeCnt.toNumber(), before.totalStakeCnt.toNumber());
+    assert.equal(
+      snapshotInfo.totalStakeWeight.toNumber(),
+      before.totalStakeWeight.toNumber() - before.receiptWeight.toNumber()
+    );
+
+    await expectError(takeSnapshot(), "SnapshotTooEarly");
+  });
+
+  it("checkpoints user stake at the boundary on next change", async () => {
+    const globalInfo = await program.account.globalPool.fetch(globalPool);
+    const checkpoint = await checkpointOf(user.publicKey);
+    assert.isNull(await program.account.stakeCheckpoint.fetchNullable(checkpoint));
+
+    await unlockPnft(user, mint);
+
+    // Stake held at the boundary is kept though the NFT is unstaked now
+    const checkpointInfo = await program.account.stakeCheckpoint.fetch(checkpoint);
+    assert.ok(checkpointInfo.user.equals(user.publicKey));
+    assert.equal(checkpointInfo.toEpoch, globalInfo.epoch);
+    assert.isAtMost(checkpointInfo.fromEpoch, globalInfo.epoch);
+    assert.equal(checkpointInfo.stakeCnt, 1);
+
+    const userInfo = await program.account.userPool.fetch(userPoolOf(user.publicKey));
+    assert.equal(userInfo.stakeCnt, 0);
+    assert.equal(userInfo.checkpointEpoch, globalInfo.epoch);
+  });
+});
@@ -0,0 +1,113 @@
+import * as anchor from "@project-serum/anchor";
+import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { assert } from "chai";
+
+import {
+  admin,
+  chainTime,
+  ensureInitialized,
+  expectError,
+  findPda,
+  globalPool,
+  newUser,
+  program,
+  userPoolOf,
+} from "./helpers";
+import { REDEMPTION_SEED, STORE_ITEM_SEED } from "../lib/constant";
+
+describe("points store", () => {
+  const price = 100;
+
+  let user: Keypair;
+  let other: Keypair;
+  let storeItem: PublicKey;
+
+  const adjustPoints = (wallet: PublicKey, delta: number) =>
+    program.methods
+      .adjustPoints(new anchor.BN(delta), 0)
+      .accounts({ admin: admin.publicKey, globalPool, userPool: userPoolOf(wallet) })
+      .rpc();
+
+  const createStoreItem = async (supply: number, perWalletLimit: number, startTime: number) => {
+    const id = Math.floor(Math.random() * 2 ** 32);
+    const idBytes = Buffer.alloc(4);
+    idBytes.writeUInt32LE(id);
+    const item = findPda([Buffer.from(STORE_ITEM_SEED), idBytes]);
+    await program.methods
+      .createStoreItem(
+        id,
+        new anchor.BN(price),
+        supply,
+        perWalletLimit,
+        new anchor.BN(startTime),
+        new anchor.BN(startTime + 3600)
+      )
+      .accounts({
+        admin: admin.publicKey,
+        globalPool,
+        storeItem: item,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    return item;
+  };
+
+  const redemptionOf = (item: PublicKey, wallet: PublicKey) =>
+    findPda([Buffer.from(REDEMPTION_SEED), item.toBuffer(), wallet.toBuffer()]);
+
+  const redeemItem = (wallet: Keypair, item: PublicKey, quantity: number) =>
+    program.methods
+      .redeemItem(quantity)
+      .accounts({
+        globalPool,
+        signer: wallet.publicKey,
+        userPool: userPoolOf(wallet.publicKey),
+        storeItem: item,
+        redemption: redemptionOf(item, wallet.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([wallet])
+      .rpc();
+
+  const points = async (wallet: Keypair) =>
+    (await program.account.userPool.fetch(userPoolOf(wallet.publicKey))).points.toNumber();
+
+  before(async () => {
+    await ensureInitialized();
+    user = await newUser();
+    other = await newUser();
+    await adjustPoints(user.publicKey, 10 * price);
+    await adjustPoints(other.publicKey, price / 2);
+    storeItem = await createStoreItem(3, 2, (await chainTime()) - 60);
+  });
+
+  it("enforces per wallet limit", async () => {
+    await expectError(redeemItem(user, storeItem, 3), "ExceedWalletLimit");
+
+    await redeemItem(user, storeItem, 2);
+    assert.equal(await points(user), 8 * price);
+
+    const redemption = await program.account.redemption.fetch(
+      redemptionOf(storeItem, user.publicKey)
+    );
+    assert.equal(redemption.quantity, 2);
+    assert.equal(redemption.pointsSpent.toNumber(), 2 * price);
+    assert.ok(redemption.user.equals(user.publicKey));
+
+    await expectError(redeemItem(user, storeItem, 1), "ExceedWalletLimit");
+  });
+
+  it("enforces supply and points balance", async () => {
+    await expectError(redeemItem(other, storeItem, 2), "SoldOut");
+    await expectError(redeemItem(other, storeItem, 1), "InsufficientPoints");
+
+    const item = await program.account.storeItem.fetch(storeItem);
+    assert.equal(item.sold, 2);
+  });
+
+  it("rejects redemption outside sale window", async () => {
+    const upcoming = await createStoreItem(3, 0, (await chainTime()) + 3600);
+    await expectError(redeemItem(user, upcoming, 1), "SaleNotActive");
+  });
+});
@@ -0,0 +1,275 @@
+import {
+  ComputeBudgetProgram,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import {
+  createAssociatedTokenAccountInstruction,
+  createInitializeGroupMemberPointerInstruction,
+  createInitializeGroupPointerInstruction,
+  createInitializeMintInstruction,
+  createInitializeNonTransferableMintInstruction,
+  createMintToInstruction,
+  ExtensionType,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  LENGTH_SIZE,
+  TOKEN_2022_PROGRAM_ID,
+  TYPE_SIZE,
+} from "@solana/spl-token";
+import {
+  createInitializeGroupInstruction,
+  createInitializeMemberInstruction,
+  TOKEN_GROUP_MEMBER_SIZE,
+  TOKEN_GROUP_SIZE,
+} from "@solana/spl-token-group";
+import { assert } from "chai";
+
+import {
+  admin,
+  blocklistOf,
+  connection,
+  ensureInitialized,
+  expectError,
+  findPda,
+  loadKeypair,
+  lockPnft,
+  mintPnft,
+  newUser,
+  pnftAccounts,
+  program,
+  provider,
+  stakedNftOf,
+  userPoolOf,
+} from "./helpers";
+import { ESCROW_SEED } from "../lib/constant";
+
+describe("token-2022 NFTs", () => {
+  // Group the program is built to accept with localnet feature
+  const group = loadKeypair("tests/fixtures/token-2022-group.json");
+
+  let user: Keypair;
+
+  const escrowOf = (mint: PublicKey) => findPda([Buffer.from(ESCROW_SEED), mint.toBuffer()]);
+
+  // Mint account with room for extensions initialized after the mint
+  const createMintAccount = async (
+    mint: PublicKey,
+    extensions: ExtensionType[],
+    extraSpace: number
+  ) => {
+    const space = getMintLen(extensions);
+    return SystemProgram.createAccount({
+      fromPubkey: admin.publicKey,
+      newAccountPubkey: mint,
+      space,
+      lamports: await connection.getMinimumBalanceForRentExemption(space + extraSpace),
+      programId: TOKEN_2022_PROGRAM_ID,
+    });
+  };
+
+  const createGroup = async () => {
+    if ((await connection.getAccountInfo(group.publicKey)) !== null) {
+      return;
+    }
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        await createMintAccount(
+          group.publicKey,
+          [ExtensionType.GroupPointer],
+          TYPE_SIZE + LENGTH_SIZE + TOKEN_GROUP_SIZE
+        ),
+        createInitializeGroupPointerInstruction(
+          group.publicKey,
+          admin.publicKey,
+          group.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          group.publicKey,
+          0,
+          admin.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeGroupInstruction({
+          programId: TOKEN_2022_PROGRAM_ID,
+          group: group.publicKey,
+          mint: group.publicKey,
+          mintAuthority: admin.publicKey,
+          updateAuthority: admin.publicKey,
+          maxSize: BigInt(100),
+        })
+      ),
+      [group]
+    );
+  };
+
+  // NFT held by owner, member of the collection group unless told otherwise
+  const mintNft = async (
+    owner: PublicKey,
+    options: { member?: boolean; nonTransferable?: boolean } = {}
+  ) => {
+    const { member = true, nonTransferable = false } = options;
+    const mint = Keypair.generate();
+    const extensions = [
+      ...(member ? [ExtensionType.GroupMemberPointer] : []),
+      ...(nonTransferable ? [ExtensionType.NonTransferable] : []),
+    ];
+    const token = getAssociatedTokenAddressSync(
+      mint.publicKey,
+      owner,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const instructions: TransactionInstruction[] = [
+      await createMintAccount(
+        mint.publicKey,
+        extensions,
+        member ? TYPE_SIZE + LENGTH_SIZE + TOKEN_GROUP_MEMBER_SIZE : 0
+      ),
+    ];
+    if (member) {
+      instructions.push(
+        createInitializeGroupMemberPointerInstruction(
+          mint.publicKey,
+          admin.publicKey,
+          mint.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+    }
+    if (nonTransferable) {
+      instructions.push(
+        createInitializeNonTransferableMintInstruction(mint.publicKey, TOKEN_2022_PROGRAM_ID)
+      );
+    }
+    instructions.push(
+      createInitializeMintInstruction(mint.publicKey, 0, admin.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    );
+    if (member) {
+      instructions.push(
+        createInitializeMemberInstruction({
+          programId: TOKEN_2022_PROGRAM_ID,
+          member: mint.publicKey,
+          memberMint: mint.publicKey,
+          memberMintAuthority: admin.publicKey,
+          group: group.publicKey,
+          groupUpdateAuthority: admin.publicKey,
+        })
+      );
+    }
+    instructions.push(
+      createAssociatedTokenAccountInstruction(
+        admin.publicKey,
+        token,
+        owner,
+        mint.publicKey,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createMintToInstruction(mint.publicKey, token, admin.publicKey, 1, [], TOKEN_2022_PROGRAM_ID)
+    );
+
+    await provider.sendAndConfirm(new Transaction().add(...instructions), [mint]);
+    return mint.publicKey;
+  };
+
+  // Token-2022 NFTs have no Metaplex accounts and are held in escrow
+  const nftAccounts = async (mint: PublicKey) => ({
+    ...(await pnftAccounts(user.publicKey, mint)),
+    tokenAccount: getAssociatedTokenAddressSync(
+      mint,
+      user.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    ),
+    escrowTokenAccount: escrowOf(mint),
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  const lockNft = async (mint: PublicKey) =>
+    program.methods
+      .lockPnft(0, 0, [], false)
+      .accounts({
+        ...(await nftAccounts(mint)),
+        walletBlocklist: blocklistOf(user.publicKey),
+        mintBlocklist: blocklistOf(mint),
+      })
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .signers([user])
+      .rpc();
+
+  const unlockNft = async (mint: PublicKey) =>
+    program.methods
+      .unlockPnft()
+      .accounts(await nftAccounts(mint))
+      .signers([user])
+      .rpc();
+
+  before(async () => {
+    await ensureInitialized();
+    await createGroup();
+    user = await newUser();
+  });
+
+  it("escrows group member NFT while staked", async () => {
+    const mint = await mintNft(user.publicKey);
+    await lockNft(mint);
+
+    const stakedInfo = await program.account.stakedNft.fetch(stakedNftOf(mint));
+    assert.deepEqual(stakedInfo.custody, { escrow: {} });
+    const escrow = await getAccount(connection, escrowOf(mint), undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(escrow.amount, BigInt(1));
+    assert.ok(escrow.owner.equals(userPoolOf(user.publicKey)));
+
+    await unlockNft(mint);
+
+    const token = await getAccount(
+      connection,
+      getAssociatedTokenAddressSync(mint, user.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(token.amount, BigInt(1));
+    assert.isNull(await connection.getAccountInfo(escrowOf(mint)));
+    assert.isNull(await connection.getAccountInfo(stakedNftOf(mint)));
+  });
+
+  it("rejects mint outside collection group", async () => {
+    const mint = await mintNft(user.publicKey, { member: false });
+    await expectError(lockNft(mint), "InvalidCollection");
+  });
+
+  it("rejects non-transferable NFT", async () => {
+    const mint = await mintNft(user.publicKey, { nonTransferable: true });
+    await expectError(lockNft(mint), "NonTransferableMint");
+  });
+
+  it("rejects escrow for legacy token NFT", async () => {
+    const mint = await mintPnft(user.publicKey);
+    await expectError(
+      program.methods
+        .lockPnft(0, 0, [], false)
+        .accounts({
+          ...(await pnftAccounts(user.publicKey, mint)),
+          escrowTokenAccount: escrowOf(mint),
+          walletBlocklist: blocklistOf(user.publicKey),
+          mintBlocklist: blocklistOf(mint),
+        })
+        .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+        .signers([user])
+        .rpc(),
+      "InvalidCustody"
+    );
+
+    // Same pNFT still locks in place without escrow
+    await lockPnft(user, mint);
+    const stakedInfo = await program.account.stakedNft.fetch(stakedNftOf(mint));
+    assert.deepEqual(stakedInfo.custody, { tokenMetadataLock: {} });
+  });
+});
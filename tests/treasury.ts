@@ -0,0 +1,80 @@
+import * as anchor from "@project-serum/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { assert } from "chai";
+
+import {
+  admin,
+  applyConfig,
+  connection,
+  ensureInitialized,
+  expectError,
+  globalPool,
+  lockPnft,
+  mintPnft,
+  newUser,
+  program,
+  treasury,
+  unlockPnft,
+} from "./helpers";
+
+describe("treasury", () => {
+  const lockFee = new anchor.BN(LAMPORTS_PER_SOL / 100);
+  const unlockFee = new anchor.BN(LAMPORTS_PER_SOL / 50);
+
+  let user: Keypair;
+  let mint: PublicKey;
+
+  const withdrawTreasury = (amount: number, signer: Keypair = admin) =>
+    program.methods
+      .withdrawTreasury(new anchor.BN(amount))
+      .accounts({
+        admin: signer.publicKey,
+        globalPool,
+        treasury,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    await ensureInitialized();
+    user = await newUser();
+    mint = await mintPnft(user.publicKey);
+    await applyConfig({ fees: { lockFee, unlockFee } });
+  });
+
+  after(async () => {
+    await applyConfig({ fees: { lockFee: new anchor.BN(0), unlockFee: new anchor.BN(0) } });
+  });
+
+  it("collects lock and unlock fees", async () => {
+    const before = await connection.getBalance(treasury);
+    await lockPnft(user, mint);
+    assert.equal(await connection.getBalance(treasury), before + lockFee.toNumber());
+
+    await unlockPnft(user, mint);
+    assert.equal(
+      await connection.getBalance(treasury),
+      before + lockFee.toNumber() + unlockFee.toNumber()
+    );
+  });
+
+  it("only lets admin withdraw", async () => {
+    await expectError(withdrawTreasury(1, user), "InvalidAdmin");
+  });
+
+  it("keeps treasury rent exempt", async () => {
+    const balance = await connection.getBalance(treasury);
+    const rentExempt = await connection.getMinimumBalanceForRentExemption(0);
+    await expectError(withdrawTreasury(balance), "InsufficientTreasury");
+
+    const adminBefore = await connection.getBalance(admin.publicKey);
+    await withdrawTreasury(balance - rentExempt);
+    assert.equal(await connection.getBalance(treasury), rentExempt);
+    // Admin pays signature fee of the withdrawal
+    assert.equal(
+      await connection.getBalance(admin.publicKey),
+      adminBefore + balance - rentExempt - 5000
+    );
+  });
+});
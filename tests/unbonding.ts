@@ -0,0 +1,128 @@
+import * as anchor from "@project-serum/anchor";
+import { ComputeBudgetProgram, Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TokenState } from "@metaplex-foundation/mpl-token-metadata";
+import { assert } from "chai";
+
+import {
+  addBlocklist,
+  applyConfig,
+  blocklistOf,
+  checkpointOf,
+  connection,
+  ensureInitialized,
+  expectError,
+  globalPool,
+  lockPnft,
+  mintPnft,
+  newUser,
+  pnftAccounts,
+  program,
+  removeBlocklist,
+  sleep,
+  stakedNftOf,
+  tokenRecordOf,
+  unlockPnft,
+  userPoolOf,
+} from "./helpers";
+
+describe("unbonding", () => {
+  const unbondingPeriod = 2;
+
+  let user: Keypair;
+  let mint: PublicKey;
+
+  const positionAccounts = async () => ({
+    globalPool,
+    signer: user.publicKey,
+    userPool: userPoolOf(user.publicKey),
+    stakedNft: stakedNftOf(mint),
+    stakeCheckpoint: await checkpointOf(user.publicKey),
+    systemProgram: SystemProgram.programId,
+  });
+
+  const requestUnlock = async () =>
+    program.methods
+      .requestUnlock()
+      .accounts(await positionAccounts())
+      .signers([user])
+      .rpc();
+
+  const cancelUnlock = async () =>
+    program.methods
+      .cancelUnlock()
+      .accounts({
+        ...(await positionAccounts()),
+        walletBlocklist: blocklistOf(user.publicKey),
+        mintBlocklist: blocklistOf(mint),
+      })
+      .signers([user])
+      .rpc();
+
+  const completeUnlock = async () =>
+    program.methods
+      .completeUnlock()
+      .accounts(await pnftAccounts(user.publicKey, mint))
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .signers([user])
+      .rpc();
+
+  const stakeCnt = async () =>
+    (await program.account.userPool.fetch(userPoolOf(user.publicKey))).stakeCnt;
+
+  before(async () => {
+    await ensureInitialized();
+    user = await newUser();
+    mint = await mintPnft(user.publicKey);
+    await applyConfig({ unbondingPeriod: { period: new anchor.BN(unbondingPeriod) } });
+    await lockPnft(user, mint);
+  });
+
+  after(async () => {
+    await applyConfig({ unbondingPeriod: { period: new anchor.BN(0) } });
+  });
+
+  it("requires unbonding instead of instant unlock", async () => {
+    await expectError(unlockPnft(user, mint), "UnbondingRequired");
+  });
+
+  it("stops counting requested NFT and holds it until period ends", async () => {
+    await requestUnlock();
+    assert.equal(await stakeCnt(), 0);
+    const stakedInfo = await program.account.stakedNft.fetch(stakedNftOf(mint));
+    assert.isAbove(stakedInfo.unlockRequestedAt.toNumber(), 0);
+
+    await expectError(completeUnlock(), "StillLocked");
+    await expectError(requestUnlock(), "UnlockAlreadyRequested");
+  });
+
+  it("puts NFT back into staking on cancel", async () => {
+    await cancelUnlock();
+    assert.equal(await stakeCnt(), 1);
+    const stakedInfo = await program.account.stakedNft.fetch(stakedNftOf(mint));
+    assert.equal(stakedInfo.unlockRequestedAt.toNumber(), 0);
+
+    await expectError(cancelUnlock(), "UnlockNotRequested");
+  });
+
+  it("doesn't let blocked wallet or mint cancel", async () => {
+    await requestUnlock();
+
+    await addBlocklist(user.publicKey);
+    await expectError(cancelUnlock(), "WalletBlocked");
+    await removeBlocklist(user.publicKey);
+
+    await addBlocklist(mint);
+    await expectError(cancelUnlock(), "MintBlocked");
+    await removeBlocklist(mint);
+  });
+
+  it("completes unlock after unbonding period", async () => {
+    await sleep((unbondingPeriod + 1) * 1000);
+    await completeUnlock();
+
+    assert.isNull(await connection.getAccountInfo(stakedNftOf(mint)));
+    assert.equal(await stakeCnt(), 0);
+    const record = await tokenRecordOf(user.publicKey, mint);
+    assert.equal(record.state, TokenState.Unlocked);
+  });
+});
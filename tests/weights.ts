@@ -0,0 +1,80 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { assert } from "chai";
+
+import {
+  applyConfig,
+  ensureInitialized,
+  expectError,
+  lockPnft,
+  mintPnft,
+  newUser,
+  program,
+  stakedNftOf,
+  unlockPnft,
+  userPoolOf,
+} from "./helpers";
+
+// Leaf of a mint weight, as GlobalPool::rarity_weight hashes it
+const weightLeaf = (mint: PublicKey, weight: number) => {
+  const weightBytes = Buffer.alloc(2);
+  weightBytes.writeUInt16LE(weight);
+  return Buffer.from(keccak_256(Buffer.concat([mint.toBuffer(), weightBytes])));
+};
+
+// Pairs are hashed in sorted order
+const hashPair = (a: Buffer, b: Buffer) =>
+  Buffer.from(keccak_256(Buffer.compare(a, b) <= 0 ? Buffer.concat([a, b]) : Buffer.concat([b, a])));
+
+describe("rarity weights", () => {
+  let user: Keypair;
+  let rare: PublicKey;
+  let common: PublicKey;
+  let rareLeaf: Buffer;
+  let commonLeaf: Buffer;
+
+  before(async () => {
+    await ensureInitialized();
+    user = await newUser();
+    rare = await mintPnft(user.publicKey);
+    common = await mintPnft(user.publicKey);
+
+    rareLeaf = weightLeaf(rare, 5);
+    commonLeaf = weightLeaf(common, 1);
+    const root = hashPair(rareLeaf, commonLeaf);
+    await applyConfig({ weightRoot: { root: Array.from(root) } });
+  });
+
+  after(async () => {
+    await applyConfig({ weightRoot: { root: new Array(32).fill(0) } });
+  });
+
+  it("rejects weight not in published root", async () => {
+    await expectError(
+      lockPnft(user, rare, { weight: 6, proof: [Array.from(commonLeaf)] }),
+      "InvalidWeightProof"
+    );
+    await expectError(lockPnft(user, rare, { weight: 5 }), "InvalidWeightProof");
+  });
+
+  it("weighs staked NFTs by proved rarity", async () => {
+    await lockPnft(user, rare, { weight: 5, proof: [Array.from(commonLeaf)] });
+    await lockPnft(user, common, { weight: 1, proof: [Array.from(rareLeaf)] });
+
+    const rareInfo = await program.account.stakedNft.fetch(stakedNftOf(rare));
+    const commonInfo = await program.account.stakedNft.fetch(stakedNftOf(common));
+    assert.equal(rareInfo.rarityWeight, 5);
+    assert.equal(commonInfo.rarityWeight, 1);
+    assert.equal(rareInfo.weight.toNumber(), 5 * commonInfo.weight.toNumber());
+
+    const userInfo = await program.account.userPool.fetch(userPoolOf(user.publicKey));
+    assert.equal(userInfo.stakeCnt, 2);
+    assert.equal(
+      userInfo.stakeWeight.toNumber(),
+      rareInfo.weight.toNumber() + commonInfo.weight.toNumber()
+    );
+
+    await unlockPnft(user, rare);
+    await unlockPnft(user, common);
+  });
+});